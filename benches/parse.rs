@@ -0,0 +1,30 @@
+//! Times parsing `ntoskrnl.exe` with and without validating its headers, see
+//! [`PeView::parse_unchecked`].
+//!
+//! Run with `cargo bench --bench parse`.
+
+use peview::file::PeView;
+use std::{error::Error, fs, hint::black_box, time::Instant};
+
+const ROUNDS: u32 = 1000;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let buf = fs::read("etc/exe/ntoskrnl.exe")?;
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(PeView::parse(black_box(&buf))?);
+    }
+    let checked = start.elapsed() / ROUNDS;
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(PeView::parse_unchecked(black_box(&buf))?);
+    }
+    let unchecked = start.elapsed() / ROUNDS;
+
+    println!("parse:           {checked:?}");
+    println!("parse_unchecked: {unchecked:?}");
+
+    Ok(())
+}
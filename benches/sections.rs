@@ -0,0 +1,47 @@
+//! Times resolving every relocation of `ntoskrnl.exe` to its section, once through
+//! [`PeView::section_by_addr`] and once through a linear scan of the section table.
+//!
+//! Run with `cargo bench --bench sections`.
+
+use peview::file::{PeAddr, PeView};
+use std::{error::Error, fs, hint::black_box, time::Instant};
+
+const ROUNDS: u32 = 20;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let buf = fs::read("etc/exe/ntoskrnl.exe")?;
+    let pe = PeView::parse(&buf)?;
+
+    let mut rvas = Vec::new();
+    for block in pe.relocations()? {
+        for reloc in block? {
+            rvas.push(reloc?.rva);
+        }
+    }
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for &rva in &rvas {
+            black_box(pe.section_by_addr(PeAddr::Rva(rva)));
+        }
+    }
+    let indexed = start.elapsed() / ROUNDS;
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for &rva in &rvas {
+            black_box(
+                pe.sections()
+                    .iter()
+                    .find(|s| !s.empty() && s.contains_addr(PeAddr::Rva(rva))),
+            );
+        }
+    }
+    let linear = start.elapsed() / ROUNDS;
+
+    println!("{} relocations", rvas.len());
+    println!("section_by_addr: {indexed:?}");
+    println!("linear scan:     {linear:?}");
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::{dir::*, error::*, impl_from_bytes, mem::*};
-use core::mem;
+use alloc::{format, vec::Vec};
+use core::{iter::FusedIterator, mem};
 
 /// Attribute certificate
 pub struct Certificate<'a> {
@@ -8,6 +9,9 @@ pub struct Certificate<'a> {
 }
 
 impl<'a> Certificate<'a> {
+    pub const TYPE_X509: u16 = 1;
+    pub const TYPE_PKCS_SIGNED_DATA: u16 = 2;
+
     pub fn new(bytes: &'a [u8], head: &'a CertificateHead) -> Self {
         Self {
             data: ByteReader::new(bytes),
@@ -29,6 +33,15 @@ impl<'a> Certificate<'a> {
     pub fn value(&self) -> &ByteReader<'a> {
         &self.data
     }
+
+    /// Returns the DER-encoded PKCS#7 `SignedData` of this [`Certificate`], which
+    /// Authenticode signatures are stored as.
+    ///
+    /// Returns [`None`] if the certificate is not of type
+    /// [`Certificate::TYPE_PKCS_SIGNED_DATA`].
+    pub fn pkcs7_der(&self) -> Option<&'a [u8]> {
+        (self.head.typ == Self::TYPE_PKCS_SIGNED_DATA).then_some(self.data.bytes())
+    }
 }
 
 /// Iterator over the certificate table
@@ -36,28 +49,60 @@ pub struct CertificateTable<'a> {
     data: ByteReader<'a>,
 }
 
+impl<'a> CertificateTable<'a> {
+    /// Drains this [`CertificateTable`] into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<Certificate<'a>>> {
+        self.collect()
+    }
+}
+
 impl<'a> Iterator for CertificateTable<'a> {
     type Item = Result<Certificate<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.data.read::<CertificateHead>() {
             Ok(head) => {
-                let data = &self.data.remaining_bytes()
-                    [..head.length as usize - mem::size_of::<CertificateHead>()];
+                // The certificate must at least hold its head and may not exceed the table
+                let data = match (head.length as usize)
+                    .checked_sub(mem::size_of::<CertificateHead>())
+                    .and_then(|size| self.data.remaining_bytes().get(..size))
+                {
+                    Some(v) => v,
+                    None => {
+                        // Stop iterating, since the following certificate can not be located
+                        self.data
+                            .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+
+                        return Some(Error::make_malformed::<CertificateHead, _>(
+                            format!("has invalid length ({:#x})", head.length),
+                        ));
+                    }
+                };
 
+                // Certificates are padded to an 8 byte boundary
                 self.data.skip_to(Pos::Rel(
-                    algin_up(head.length as _, 8)
+                    align_up(head.length as _, 8)
                         - mem::size_of::<CertificateHead>(),
                 ));
 
                 Some(Ok(Certificate::new(data, head)))
             }
-            Err(Error::InsufficientBuffer) => None,
-            Err(e) => Some(Err(e)),
+            Err(e) if e.is_insufficient_buffer() => None,
+            Err(e) => {
+                self.data
+                    .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+                Some(Err(e))
+            }
         }
     }
 }
 
+impl<'a> FusedIterator for CertificateTable<'a> {}
+
 impl<'a> DataDirectoryTable<'a> for CertificateTable<'a> {
     fn new(bytes: &'a [u8], _dir: &'a DataDirectory) -> Self {
         Self {
@@ -0,0 +1,257 @@
+use crate::{dir::*, error::*, impl_from_bytes, mem::*};
+use alloc::{format, vec::Vec};
+use core::{iter::FusedIterator, mem};
+
+/// Parsed data of a debug directory entry, see [`crate::file::PeView::debug_entries`]
+pub enum DebugEntry<'a> {
+    /// Location of the PDB file in the RSDS format
+    CodeView(CodeView<'a>),
+    /// Profile guided optimization data, listing the contributions to each section
+    Pogo(Pogo<'a>),
+    /// Hash of a reproducible build, which replaces the timestamps of the image
+    Repro(&'a [u8]),
+    /// Counts of the functions compiled with the security features of MSVC
+    VcFeature(VcFeature),
+    /// Entry of any other type, holding the type and its raw data
+    Raw(u32, &'a [u8]),
+}
+
+impl<'a> DebugEntry<'a> {
+    /// Parses the specified data of the specified [`DebugDirectoryEntry`].
+    ///
+    /// CodeView entries which are not in the RSDS format are returned as [`DebugEntry::Raw`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data is malformed.
+    pub fn parse(entry: &DebugDirectoryEntry, data: &'a [u8]) -> Result<Self> {
+        match entry.typ {
+            DebugDirectoryEntry::TYPE_CODEVIEW
+                if data.len() >= mem::size_of::<u32>()
+                    && u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+                        == CodeViewHead::RSDS_SIGNATURE =>
+            {
+                let mut reader = ByteReader::new(data);
+                let head = reader.read::<CodeViewHead>()?;
+                let path = str_from_bytes(reader.remaining_bytes())?;
+
+                Ok(Self::CodeView(CodeView { head, path }))
+            }
+            DebugDirectoryEntry::TYPE_POGO => {
+                let mut reader = ByteReader::new(data);
+                let signature = *reader.read::<u32>()?;
+
+                Ok(Self::Pogo(Pogo {
+                    signature,
+                    data: reader,
+                }))
+            }
+            DebugDirectoryEntry::TYPE_VC_FEATURE => {
+                Ok(Self::VcFeature(VcFeature::from_bytes_copied(data)?))
+            }
+            DebugDirectoryEntry::TYPE_REPRO => {
+                // The hash is prefixed by its length, older linkers emit no data at all
+                if data.is_empty() {
+                    return Ok(Self::Repro(data));
+                }
+
+                let mut reader = ByteReader::new(data);
+                let length = *reader.read::<u32>()?;
+                let hash = reader
+                    .remaining_bytes()
+                    .get(..length as usize)
+                    .ok_or_else(|| {
+                        Error::Malformed(format!(
+                            "repro debug entry has invalid hash length ({length:#x})"
+                        ))
+                    })?;
+
+                Ok(Self::Repro(hash))
+            }
+            typ => Ok(Self::Raw(typ, data)),
+        }
+    }
+}
+
+/// CodeView debug information in the RSDS format, referring to a PDB file
+pub struct CodeView<'a> {
+    head: &'a CodeViewHead,
+    path: &'a str,
+}
+
+impl<'a> CodeView<'a> {
+    /// Returns the GUID of the PDB file, as stored in the image.
+    pub fn guid(&self) -> [u8; 16] {
+        self.head.guid
+    }
+
+    /// Returns the age of the PDB file, which is incremented on each update.
+    pub fn age(&self) -> u32 {
+        self.head.age
+    }
+
+    /// Returns the path of the PDB file at build time.
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+}
+
+/// Iterator over the sections contributions of a profile guided optimization entry
+pub struct Pogo<'a> {
+    signature: u32,
+    data: ByteReader<'a>,
+}
+
+impl<'a> Pogo<'a> {
+    /// Returns the signature of this [`Pogo`] entry, like `PGU\0` or `PGI\0`
+    /// in little endian.
+    pub fn signature(&self) -> u32 {
+        self.signature
+    }
+
+    /// Drains this [`Pogo`] entry into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<PogoEntry<'a>>> {
+        self.collect()
+    }
+}
+
+impl<'a> Iterator for Pogo<'a> {
+    type Item = Result<PogoEntry<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (|| -> Result<_> {
+            let rva = *self.data.read::<u32>()?;
+            let size = *self.data.read::<u32>()?;
+            let name = str_from_bytes(self.data.remaining_bytes())?;
+
+            // Names are null-terminated and padded to a 4 byte boundary
+            self.data.skip_to(Pos::Rel(align_up(name.len() + 1, 4)));
+
+            Ok(PogoEntry { rva, size, name })
+        })() {
+            Ok(v) => Some(Ok(v)),
+            Err(e) if e.is_insufficient_buffer() => None,
+            Err(e) => {
+                self.data
+                    .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for Pogo<'a> {}
+
+/// Contribution of a profile guided optimization entry to a section
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PogoEntry<'a> {
+    /// RVA of the contribution
+    pub rva: u32,
+    /// Size of the contribution
+    pub size: u32,
+    /// Name of the contribution, like `.text$mn`
+    pub name: &'a str,
+}
+
+/// Iterator over the entries of the debug directory
+pub struct DebugTable<'a> {
+    data: ByteReader<'a>,
+}
+
+impl<'a> DebugTable<'a> {
+    /// Drains this [`DebugTable`] into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<&'a DebugDirectoryEntry>> {
+        self.collect()
+    }
+}
+
+impl<'a> Iterator for DebugTable<'a> {
+    type Item = Result<&'a DebugDirectoryEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.data.read::<DebugDirectoryEntry>() {
+            Ok(entry) => Some(Ok(entry)),
+            Err(e) if e.is_insufficient_buffer() => None,
+            Err(e) => {
+                self.data
+                    .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for DebugTable<'a> {}
+
+impl<'a> DataDirectoryTable<'a> for DebugTable<'a> {
+    fn new(bytes: &'a [u8], _dir: &'a DataDirectory) -> Self {
+        Self {
+            data: ByteReader::new(bytes),
+        }
+    }
+
+    fn typ() -> DataDirectoryType {
+        DataDirectoryType::Debug
+    }
+}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#debug-directory-image-only)
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct DebugDirectoryEntry {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub typ: u32,
+    pub size_of_data: u32,
+    pub address_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+}
+
+impl DebugDirectoryEntry {
+    pub const TYPE_CODEVIEW: u32 = 2;
+    pub const TYPE_VC_FEATURE: u32 = 12;
+    pub const TYPE_POGO: u32 = 13;
+    pub const TYPE_REPRO: u32 = 16;
+}
+
+/// Head of CodeView debug information in the RSDS format, followed by the path
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct CodeViewHead {
+    pub signature: u32,
+    pub guid: [u8; 16],
+    pub age: u32,
+}
+
+impl CodeViewHead {
+    pub const RSDS_SIGNATURE: u32 = 0x53445352;
+}
+
+/// Data of a VC feature debug directory entry, counting the functions compiled with
+/// each of the security features of MSVC
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct VcFeature {
+    /// Functions compiled by a compiler older than Visual C++ 11
+    pub pre_vc11_count: u32,
+    /// Functions compiled as C or C++
+    pub c_cpp_count: u32,
+    /// Functions protected by buffer security checks (`/GS`)
+    pub gs_count: u32,
+    /// Functions compiled with additional security checks (`/sdl`)
+    pub sdl_count: u32,
+    /// Functions instrumented for control flow guard (`/guard:cf`)
+    pub guard_count: u32,
+}
+
+impl_from_bytes!(DebugDirectoryEntry, CodeViewHead, VcFeature);
@@ -1,6 +1,6 @@
 use crate::{dir::*, error::*, impl_from_bytes, mem::*};
-use alloc::string::ToString;
-use core::mem;
+use alloc::{vec, vec::Vec};
+use core::{iter::FusedIterator, mem};
 
 /// The value of a single export entry
 pub enum ExportValue<'a> {
@@ -20,12 +20,21 @@ pub struct Export<'a> {
     pub name: Option<&'a str>,
 }
 
+impl Export<'_> {
+    /// Checks if this [`Export`] is forwarded to another module.
+    pub fn is_forwarded(&self) -> bool {
+        matches!(self.value, ExportValue::Forward(_))
+    }
+}
+
 /// Iterator over the export table located in .edata
 pub struct ExportTable<'a> {
     dir: &'a DataDirectory,
     export_table: Option<&'a ExportDirectoryTable>,
-    index: (u16, usize),
+    index: (u32, usize),
+    name_indices: Option<Vec<Option<u32>>>,
     data: ByteReader<'a>,
+    done: bool,
 }
 
 impl<'a> ExportTable<'a> {
@@ -38,13 +47,13 @@ impl<'a> ExportTable<'a> {
     /// This function will return an error if it was unable to parse the table
     /// or the table was malformed
     pub fn export_table(&mut self) -> Result<&'a ExportDirectoryTable> {
-        if self.export_table.is_none() {
+        if let Some(etable) = self.export_table {
+            Ok(etable)
+        } else {
             let etable = self.data.read::<ExportDirectoryTable>()?.validate()?;
             self.data.skip_to(Pos::Abs(etable.function_rva as _));
 
             Ok(self.export_table.insert(etable))
-        } else {
-            Ok(self.export_table.unwrap())
         }
     }
 
@@ -59,6 +68,9 @@ impl<'a> ExportTable<'a> {
 
     /// Returns the `num_of_funcs` field of the [`ExportDirectoryTable`]
     ///
+    /// This is the number of EAT slots, which includes unused slots that are
+    /// skipped by the iterator.
+    ///
     /// # Errors
     ///
     /// This function will return an error if it was unable to retrieve the [`ExportDirectoryTable`]
@@ -74,6 +86,222 @@ impl<'a> ExportTable<'a> {
     pub fn name_count(&mut self) -> Result<u32> {
         Ok(self.export_table()?.num_of_names)
     }
+
+    /// Returns the name of the module as declared by the [`ExportDirectoryTable`],
+    /// which can differ from the name of the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it was unable to retrieve the [`ExportDirectoryTable`]
+    /// or the name is not a valid, null-terminated UTF-8 string.
+    pub fn module_name(&mut self) -> Result<&'a str> {
+        let name_rva = self.export_table()?.name_rva;
+
+        str_from_bytes(self.data.bytes_at(name_rva as _)?)
+    }
+
+    /// Returns the `ordinal_base` field of the [`ExportDirectoryTable`]
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it was unable to retrieve the [`ExportDirectoryTable`]
+    pub fn ordinal_base(&mut self) -> Result<u32> {
+        Ok(self.export_table()?.ordinal_base)
+    }
+
+    /// Returns the `major_version` and `minor_version` fields of the [`ExportDirectoryTable`]
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it was unable to retrieve the [`ExportDirectoryTable`]
+    pub fn version(&mut self) -> Result<(u16, u16)> {
+        let etable = self.export_table()?;
+
+        Ok((etable.major_version, etable.minor_version))
+    }
+
+    /// Returns the number of forwarded exports, without decoding their strings
+    /// or advancing this [`ExportTable`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it was unable to retrieve the [`ExportDirectoryTable`]
+    /// or the EAT is outside of the buffers bounds.
+    pub fn forwarded_count(&mut self) -> Result<usize> {
+        let funcs = self.function_rvas()?;
+
+        // Forwarded exports point to their string within the export directory
+        Ok(funcs
+            .iter()
+            .filter(|&&rva| self.dir.contains_addr(rva))
+            .count())
+    }
+
+    /// Returns the EAT, the RVAs of every exported function indexed by their ordinal
+    /// minus the ordinal base, without advancing this [`ExportTable`].
+    ///
+    /// Unused slots are zero and forwarded exports point to their string.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it was unable to retrieve the [`ExportDirectoryTable`]
+    /// or the EAT is outside of the buffers bounds.
+    pub fn function_rvas(&mut self) -> Result<&'a [u32]> {
+        let etable = self.export_table()?;
+
+        self.data
+            .bytes_at(etable.function_rva as _)
+            .and_then(|b| u32::slice_from_bytes(b, etable.num_of_funcs as _))
+            .map_err(|e| e.at(etable.function_rva as _))
+    }
+
+    /// Resolves the specified [`Import`] of another module against this [`ExportTable`],
+    /// like the loader does.
+    ///
+    /// Imports by name are first looked up at the ENPT index given by their hint,
+    /// falling back to searching the whole ENPT if the name at that index differs.
+    /// Imports by ordinal are looked up in the EAT directly.
+    ///
+    /// Returns [`None`] if the import is not exported.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it was unable to retrieve the [`ExportDirectoryTable`]
+    /// or the entries of the resolved export are outside of the buffers bounds.
+    pub fn resolve(&mut self, import: &Import<'_>) -> Result<Option<Export<'a>>> {
+        let etable = self.export_table()?;
+        let name_indices = 0..etable.num_of_names as usize;
+
+        let (index, name) = match *import {
+            Import::Name(hint, name) => {
+                let name_index = Some(hint as usize)
+                    .filter(|&i| {
+                        name_indices.contains(&i)
+                            && self.name_at(etable, i).is_ok_and(|n| n == name)
+                    })
+                    .or_else(|| {
+                        name_indices.clone().find(|&i| {
+                            self.name_at(etable, i).is_ok_and(|n| n == name)
+                        })
+                    });
+
+                match name_index {
+                    Some(i) => {
+                        (self.ordinal_at(etable, i)?, Some(self.name_at(etable, i)?))
+                    }
+                    None => return Ok(None),
+                }
+            }
+            Import::Ordinal(ordinal) => {
+                let index = (ordinal as u32).wrapping_sub(etable.ordinal_base);
+                let name = match self.name_index_of(etable, index)? {
+                    Some(i) => Some(self.name_at(etable, i)?),
+                    None => None,
+                };
+
+                (index, name)
+            }
+        };
+
+        // Unused slots of the EAT are not exported
+        match self.function_rvas()?.get(index as usize) {
+            Some(&rva) if rva != 0 => Ok(Some(Export {
+                value: self.value_of(rva)?,
+                ordinal: etable.ordinal_base.wrapping_add(index) as u16,
+                name,
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns an iterator over the exports which are only exported by ordinal.
+    pub fn ordinals_only(self) -> impl Iterator<Item = Result<Export<'a>>> {
+        self.filter(|e| !matches!(e, Ok(Export { name: Some(_), .. })))
+    }
+
+    /// Returns the index of the ENPT entry whose EOT entry refers to the specified
+    /// EAT index
+    ///
+    /// The EOT is inverted into a map from EAT to ENPT indices on the first call,
+    /// so unsorted or malformed EOTs are not searched once per export.
+    fn name_index_of(
+        &mut self,
+        etable: &ExportDirectoryTable,
+        index: u32,
+    ) -> Result<Option<usize>> {
+        if self.name_indices.is_none() {
+            // The size of the map is bounded by the part of the EAT within the buffer
+            let len = self
+                .data
+                .bytes_at(etable.function_rva as _)
+                .map_or(0, |b| b.len() / mem::size_of::<u32>())
+                .min(etable.num_of_funcs as usize);
+            let mut indices = vec![None; len];
+            for i in 0..etable.num_of_names as usize {
+                // Stop at the end of the buffer, the following entries are outside too
+                let Ok(ordinal) = self.ordinal_at(etable, i) else {
+                    break;
+                };
+
+                if let Some(slot @ None) = indices.get_mut(ordinal as usize) {
+                    *slot = Some(i as u32);
+                }
+            }
+
+            self.name_indices = Some(indices);
+        }
+
+        Ok(self
+            .name_indices
+            .as_ref()
+            .and_then(|indices| indices.get(index as usize).copied().flatten())
+            .map(|i| i as usize))
+    }
+
+    /// Returns the EAT index stored in the EOT entry at the specified index
+    fn ordinal_at(
+        &self,
+        etable: &ExportDirectoryTable,
+        index: usize,
+    ) -> Result<u32> {
+        Ok(*self.data.read_at::<u16>(
+            etable.ordinals_rva as usize + mem::size_of::<u16>() * index,
+        )? as u32)
+    }
+
+    /// Returns the value of the EAT entry holding the specified RVA, which is either
+    /// a forward export or a normal RVA
+    fn value_of(&self, rva: u32) -> Result<ExportValue<'a>> {
+        if self.dir.contains_addr(rva) {
+            Ok(ExportValue::Forward(str_from_bytes(
+                self.data.bytes_at(rva as usize)?,
+            )?))
+        } else {
+            Ok(ExportValue::Rva(rva))
+        }
+    }
+
+    /// Returns the name referenced by the ENPT entry at the specified index
+    fn name_at(
+        &self,
+        etable: &ExportDirectoryTable,
+        index: usize,
+    ) -> Result<&'a str> {
+        let name_rva = self.data.read_at::<u32>(
+            etable.names_rva as usize + mem::size_of::<u32>() * index,
+        )?;
+
+        str_from_bytes(self.data.bytes_at(*name_rva as usize)?)
+    }
+
+    /// Drains this [`ExportTable`] into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<Export<'a>>> {
+        self.collect()
+    }
 }
 
 impl<'a> DataDirectoryTable<'a> for ExportTable<'a> {
@@ -83,6 +311,8 @@ impl<'a> DataDirectoryTable<'a> for ExportTable<'a> {
             dir,
             export_table: None,
             index: (0, 0),
+            name_indices: None,
+            done: false,
         }
     }
 
@@ -95,62 +325,76 @@ impl<'a> Iterator for ExportTable<'a> {
     type Item = Result<Export<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
         // Get the EDT
         let etable = match self.export_table() {
             Ok(v) => v,
-            Err(e) => return Some(Err(e)),
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
         };
 
-        // Check if the iterator reached the end of the EAT
-        if etable.num_of_funcs <= self.index.0 as u32 {
-            return None;
-        }
-
         match (|| {
-            // Read the next EAT and EOT entry
-            let rva = self.data.read::<u32>()?;
-            let ordinal = self.data.read_at::<u16>(
-                etable.ordinals_rva as usize + mem::size_of::<u16>() * self.index.1,
-            )?;
-
-            // Check if the EOT entry corresponds to a ENPT entry
-            let name = if self.index.0 == *ordinal {
-                let name_rva = self.data.read_at::<u32>(
-                    etable.names_rva as usize + mem::size_of::<u32>() * self.index.1,
-                )?;
-
-                Some(str_from_bytes(self.data.bytes_at(*name_rva as usize)?)?)
-            } else {
-                None
+            // Read the next EAT entry, skipping unused slots between used ordinals
+            let (index, rva) = loop {
+                if etable.num_of_funcs <= self.index.0 {
+                    return Ok(None);
+                }
+
+                let index = self.index.0;
+                let rva = self.data.read::<u32>()?;
+                self.index.0 += 1;
+
+                if *rva != 0 {
+                    break (index, rva);
+                }
+
+                // Keep the sorted EOT fast path below in sync with the EAT
+                if self.index.1 < etable.num_of_names as usize
+                    && self.ordinal_at(etable, self.index.1)? == index
+                {
+                    self.index.1 += 1;
+                }
             };
 
-            // Advance the EAT and EOT entry indices
-            self.index.0 += 1;
-            if name.is_some() {
+            // Find the ENPT entry whose EOT entry refers to the current EAT entry.
+            // Linkers usually sort the EOT by EAT index, so the next unmatched EOT
+            // entry is checked first, before falling back to searching the whole EOT
+            let name_index = if self.index.1 < etable.num_of_names as usize
+                && self.ordinal_at(etable, self.index.1)? == index
+            {
                 self.index.1 += 1;
-            }
-
-            // Check if the current EAT entry is a forward export or a normal RVA
-            let value = if self.dir.contains_addr(*rva) {
-                ExportValue::Forward(str_from_bytes(
-                    self.data.bytes_at(*rva as usize)?,
-                )?)
+                Some(self.index.1 - 1)
             } else {
-                ExportValue::Rva(*rva)
+                self.name_index_of(etable, index)?
+            };
+
+            let name = match name_index {
+                Some(i) => Some(self.name_at(etable, i)?),
+                None => None,
             };
 
-            Ok(Export {
-                value,
-                ordinal: etable.ordinal_base as u16 + self.index.0 - 1,
+            Ok(Some(Export {
+                value: self.value_of(*rva)?,
+                ordinal: etable.ordinal_base.wrapping_add(index) as u16,
                 name,
-            })
+            }))
         })() {
-            Ok(v) => Some(Ok(v)),
-            Err(e) => Some(Err(e)),
+            Ok(v) => v.map(Ok),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
         }
     }
 }
 
+impl<'a> FusedIterator for ExportTable<'a> {}
+
 /// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#export-directory-table)
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -171,22 +415,22 @@ pub struct ExportDirectoryTable {
 impl ExportDirectoryTable {
     pub fn validate(&self) -> Result<&Self> {
         if self.characteristics != 0 {
-            return Error::make_malformed::<ExportDirectoryTable, _>(
-                "has non zero reserved field 'characteristics'".to_string(),
+            return Error::make_validation::<ExportDirectoryTable, _>(
+                ValidationRule::ReservedField("characteristics"),
             );
         }
 
         if self.num_of_funcs < self.num_of_names {
-            return Error::make_malformed::<ExportDirectoryTable, _>(
-                "has invalid number of functions or names".to_string(),
+            return Error::make_validation::<ExportDirectoryTable, _>(
+                ValidationRule::NumOfFuncsOrNames,
             );
         }
 
         if (self.names_rva == 0 && self.ordinals_rva != 0)
-            || (self.names_rva != 0 && !self.ordinals_rva == 0)
+            || (self.names_rva != 0 && self.ordinals_rva == 0)
         {
-            return Error::make_malformed::<ExportDirectoryTable, _>(
-                "has invalid rva to name or ordinal table".to_string(),
+            return Error::make_validation::<ExportDirectoryTable, _>(
+                ValidationRule::NameOrOrdinalTable,
             );
         }
 
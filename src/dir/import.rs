@@ -1,30 +1,72 @@
-use crate::{dir::*, error::*, impl_from_bytes, mem::*};
-use core::mem;
+use crate::{dir::*, error::*, header::Bitness, impl_from_bytes, mem::*};
+use alloc::vec::Vec;
+use core::{
+    iter::{self, FusedIterator},
+    mem,
+};
 
 /// Import entry of a module
 pub enum Import<'a> {
     /// Hint and name of imported symbol
+    ///
+    /// The hint is the index into the ENPT of the exporting module at which the name
+    /// is expected, see [`ExportTable::resolve`].
     Name(u16, &'a str),
     /// Ordinal value
     Ordinal(u16),
 }
 
+/// Name of an imported symbol, see [`crate::file::PeView::import_summary`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportName<'a> {
+    /// Symbol imported by name
+    Named(&'a str),
+    /// Symbol imported by ordinal
+    Ordinal(u16),
+}
+
+impl<'a> From<Import<'a>> for ImportName<'a> {
+    fn from(import: Import<'a>) -> Self {
+        match import {
+            Import::Name(_, name) => Self::Named(name),
+            Import::Ordinal(ordinal) => Self::Ordinal(ordinal),
+        }
+    }
+}
+
 /// Iterator over the import entries of a single module
 pub struct ImportModule<'a> {
     dir: &'a ImportDirectoryEntry,
     data: ByteReader<'a>,
+    bitness: Bitness,
+    done: bool,
 }
 
 impl<'a> ImportModule<'a> {
+    /// Creates a new [`ImportModule`], reading ILT entries of the specified [`Bitness`].
+    ///
+    /// If the module has no ILT, the imports are read from the IAT instead,
+    /// see [`ImportModule::uses_iat_fallback`].
     pub fn new(
         data: &'a [u8],
         data_rva: usize,
         dir: &'a ImportDirectoryEntry,
+        bitness: Bitness,
     ) -> Self {
+        let lookup_rva = match dir.lookup_rva {
+            0 => dir.address_rva,
+            v => v,
+        };
+
         let mut data = ByteReader::new_with_rel(data, data_rva);
-        data.skip_to(Pos::Abs(dir.lookup_rva as _));
+        data.skip_to(Pos::Abs(lookup_rva as _));
 
-        Self { data, dir }
+        Self {
+            data,
+            dir,
+            bitness,
+            done: false,
+        }
     }
 
     /// Returns the `time_date_stamp` field of the [`ImportDirectoryEntry`].
@@ -38,7 +80,7 @@ impl<'a> ImportModule<'a> {
     }
 
     /// Returns the parsed name of the [`ImportDirectoryEntry`].
-    pub fn name(&self) -> Result<&str> {
+    pub fn name(&self) -> Result<&'a str> {
         str_from_bytes(self.data.bytes_at(self.dir.name_rva as _)?)
     }
 
@@ -46,17 +88,160 @@ impl<'a> ImportModule<'a> {
     pub fn address_rva(&self) -> u32 {
         self.dir.address_rva
     }
+
+    /// Checks if the module has no ILT, in which case its imports are read from the IAT.
+    ///
+    /// The entries of a bound IAT are addresses instead of imports,
+    /// so they can not be parsed.
+    pub fn uses_iat_fallback(&self) -> bool {
+        self.dir.lookup_rva == 0
+    }
+
+    /// Returns an iterator over the entries of the IAT, which hold the addresses of the
+    /// imported symbols once they are bound.
+    ///
+    /// Until then, the entries usually equal the ones of the ILT.
+    pub fn addresses(&self) -> impl Iterator<Item = Result<u64>> + 'a {
+        let mut data = ByteReader::new_with_rel(
+            self.data.bytes(),
+            self.data.rel_pos().unwrap_or(0),
+        );
+        data.skip_to(Pos::Abs(self.dir.address_rva as _));
+
+        let bitness = self.bitness;
+        let mut done = false;
+        iter::from_fn(move || {
+            if done {
+                return None;
+            }
+
+            // Read the next IAT entry, which is pointer sized
+            let entry = match bitness {
+                Bitness::Bits32 => data.read::<u32>().map(|&v| v as u64),
+                Bitness::Bits64 => data.read::<u64>().copied(),
+            };
+
+            // Stop at the null terminator or the first error
+            match entry {
+                Ok(0) => {
+                    done = true;
+                    None
+                }
+                Ok(v) => Some(Ok(v)),
+                Err(e) => {
+                    done = true;
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+
+    /// Returns an iterator over the raw entries of the ILT, paired with the IAT entry
+    /// at the same index, up to the null terminator of the ILT.
+    ///
+    /// An import has been bound if its IAT entry differs from its ILT entry. Modules
+    /// without an ILT, see [`ImportModule::uses_iat_fallback`], pair the entries
+    /// of the IAT with themselves.
+    pub fn thunks(&self) -> impl Iterator<Item = Result<(ImportEntry, u64)>> + 'a {
+        let reader = |rva: u32| {
+            let mut data = ByteReader::new_with_rel(
+                self.data.bytes(),
+                self.data.rel_pos().unwrap_or(0),
+            );
+            data.skip_to(Pos::Abs(rva as _));
+            data
+        };
+        let lookup_rva = match self.dir.lookup_rva {
+            0 => self.dir.address_rva,
+            v => v,
+        };
+        let mut lookups = reader(lookup_rva);
+        let mut addresses = reader(self.dir.address_rva);
+
+        let bitness = self.bitness;
+        let mut done = false;
+        iter::from_fn(move || {
+            if done {
+                return None;
+            }
+
+            // Read the next ILT and IAT entries, which are pointer sized
+            let thunk = match bitness {
+                Bitness::Bits32 => lookups
+                    .read::<ImportEntry32>()
+                    .and_then(|&e| Ok((e.into(), *addresses.read::<u32>()? as u64))),
+                Bitness::Bits64 => lookups
+                    .read::<ImportEntry>()
+                    .and_then(|&e| Ok((e, *addresses.read::<u64>()?))),
+            };
+
+            // Stop at the null terminator of the ILT or the first error
+            match thunk {
+                Ok((entry, _)) if entry == ImportEntry::default() => {
+                    done = true;
+                    None
+                }
+                Ok(v) => Some(Ok(v)),
+                Err(e) => {
+                    done = true;
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+
+    /// Returns an iterator over the imports of this [`ImportModule`], paired with the
+    /// IAT entry at the same index.
+    ///
+    /// The IAT entry is [`None`] if the IAT is shorter than the ILT.
+    pub fn entries(self) -> impl Iterator<Item = Result<(Import<'a>, Option<u64>)>> {
+        let mut addresses = self.addresses();
+
+        self.map(move |import| Ok((import?, addresses.next().transpose()?)))
+    }
+
+    /// Advances this [`ImportModule`] to the symbol imported by the specified name
+    /// and returns it.
+    ///
+    /// Returns [`None`] if no such import is found, or the first error encountered
+    /// while searching.
+    pub fn find_by_name(&mut self, name: &str) -> Option<Result<Import<'a>>> {
+        self.find(|i| match i {
+            Ok(Import::Name(_, n)) => *n == name,
+            Ok(Import::Ordinal(_)) => false,
+            Err(_) => true,
+        })
+    }
+
+    /// Drains this [`ImportModule`] into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<Import<'a>>> {
+        self.collect()
+    }
 }
 
 impl<'a> Iterator for ImportModule<'a> {
     type Item = Result<Import<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // Read the next ILT entry
-        match self.data.read::<ImportEntry>() {
+        if self.done {
+            return None;
+        }
+
+        // Read the next ILT entry, which is pointer sized
+        let entry = match self.bitness {
+            Bitness::Bits32 => self.data.read::<ImportEntry32>().map(|&e| e.into()),
+            Bitness::Bits64 => self.data.read::<ImportEntry>().copied(),
+        };
+
+        match entry {
             Ok(entry) => {
                 // If the entry is zero, it means we reached the end of the table
-                if entry == &ImportEntry::default() {
+                if entry == ImportEntry::default() {
+                    self.done = true;
                     return None;
                 }
 
@@ -74,26 +259,57 @@ impl<'a> Iterator for ImportModule<'a> {
                         Ok((*hint, name))
                     })() {
                         Ok((o, n)) => Import::Name(o, n),
-                        Err(e) => return Some(Err(e)),
+                        Err(e) => {
+                            self.done = true;
+                            return Some(Err(e));
+                        }
                     }
                 };
 
                 Some(Ok(import))
             }
-            Err(e) => Some(Err(e)),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
         }
     }
 }
 
+impl<'a> FusedIterator for ImportModule<'a> {}
+
 /// Iterator over the import table located in .idata
 pub struct ImportTable<'a> {
     data: ByteReader<'a>,
+    bitness: Bitness,
+    done: bool,
+}
+
+impl<'a> ImportTable<'a> {
+    /// Sets the [`Bitness`] of the image this [`ImportTable`] belongs to.
+    ///
+    /// Tables created by [`DataDirectoryTable::new`] assume [`Bitness::Bits64`].
+    pub fn with_bitness(mut self, bitness: Bitness) -> Self {
+        self.bitness = bitness;
+        self
+    }
+
+    /// Drains this [`ImportTable`] into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<ImportModule<'a>>> {
+        self.collect()
+    }
 }
 
 impl<'a> DataDirectoryTable<'a> for ImportTable<'a> {
     fn new(bytes: &'a [u8], dir: &'a DataDirectory) -> Self {
         Self {
             data: ByteReader::new_with_rel(bytes, dir.addr as usize),
+            bitness: Bitness::Bits64,
+            done: false,
         }
     }
 
@@ -106,25 +322,36 @@ impl<'a> Iterator for ImportTable<'a> {
     type Item = Result<ImportModule<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
         // Read the next IDT entry
         match self.data.read::<ImportDirectoryEntry>() {
             Ok(dir) => {
                 // If the entry is zero, it means we reached the end of the table
                 if dir == &ImportDirectoryEntry::default() {
+                    self.done = true;
                     return None;
                 }
 
                 Some(Ok(ImportModule::new(
                     self.data.bytes(),
-                    self.data.rel_pos().unwrap(),
+                    self.data.rel_pos().unwrap_or_default(),
                     dir,
+                    self.bitness,
                 )))
             }
-            Err(e) => Some(Err(e)),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
         }
     }
 }
 
+impl<'a> FusedIterator for ImportTable<'a> {}
+
 /// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#import-directory-table)
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
 #[repr(C)]
@@ -137,10 +364,16 @@ pub struct ImportDirectoryEntry {
 }
 
 /// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#import-lookup-table)
-#[derive(Clone, Copy, PartialEq, Eq, Default)]
+/// for PE32+ images
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct ImportEntry(u64);
 
 impl ImportEntry {
+    /// Returns the raw value of this [`ImportEntry`].
+    pub fn raw(&self) -> u64 {
+        self.0
+    }
+
     pub fn value(&self) -> u32 {
         (self.0 & 0x00000000FFFFFFFF) as u32
     }
@@ -150,4 +383,25 @@ impl ImportEntry {
     }
 }
 
-impl_from_bytes!(ImportDirectoryEntry, ImportEntry);
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#import-lookup-table)
+/// for PE32 images
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct ImportEntry32(u32);
+
+impl ImportEntry32 {
+    pub fn value(&self) -> u32 {
+        self.0 & 0x7FFFFFFF
+    }
+
+    pub fn is_ordinal(&self) -> bool {
+        (self.0 >> 31) == 1
+    }
+}
+
+impl From<ImportEntry32> for ImportEntry {
+    fn from(entry: ImportEntry32) -> Self {
+        Self(((entry.is_ordinal() as u64) << 63) | entry.value() as u64)
+    }
+}
+
+impl_from_bytes!(ImportDirectoryEntry, ImportEntry, ImportEntry32);
@@ -0,0 +1,339 @@
+use crate::{dir::*, error::*, impl_from_bytes, mem::*};
+use alloc::format;
+use core::{iter::FusedIterator, mem};
+
+/// Load configuration of an image
+///
+/// The structure grew with every version of Windows, its `size` field declares how
+/// much of it is present. Fields beyond that size are zero.
+pub struct LoadConfig {
+    directory: LoadConfigDirectory,
+}
+
+impl LoadConfig {
+    /// Parses the [`LoadConfig`] at the start of the specified bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the declared size of the structure
+    /// exceeds the bytes.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let size = u32::from_bytes_copied(bytes)? as usize;
+        let bytes = bytes
+            .get(..size.min(mem::size_of::<LoadConfigDirectory>()))
+            .ok_or(Error::InsufficientBuffer)?;
+
+        // Copy the present part of the structure, leaving the remaining fields zero
+        let mut buf = [0u8; mem::size_of::<LoadConfigDirectory>()];
+        buf[..bytes.len()].copy_from_slice(bytes);
+
+        Ok(Self {
+            directory: LoadConfigDirectory::from_bytes_copied(&buf)?,
+        })
+    }
+
+    /// Returns a reference to the [`LoadConfigDirectory`] of this [`LoadConfig`].
+    pub fn directory(&self) -> &LoadConfigDirectory {
+        &self.directory
+    }
+
+    /// Returns the 1-based number of the section and the offset within it of the
+    /// dynamic value relocation table, see [`DynamicRelocationTable`].
+    ///
+    /// Returns [`None`] if the image has no such table.
+    pub fn dynamic_value_reloc_table(&self) -> Option<(u16, u32)> {
+        let section = self.directory.dynamic_value_reloc_table_section;
+        (section != 0)
+            .then_some((section, self.directory.dynamic_value_reloc_table_offset))
+    }
+}
+
+/// Entry of the dynamic value relocation table
+pub struct DynamicRelocation<'a> {
+    /// Symbol describing the kind of the relocation, like
+    /// [`DynamicRelocation::ARM64X`]
+    pub symbol: u64,
+    data: &'a [u8],
+}
+
+impl<'a> DynamicRelocation<'a> {
+    /// Fixups applied by the loader to turn an ARM64X image into its x64 variant
+    pub const ARM64X: u64 = 6;
+
+    /// Returns the raw fixups of this [`DynamicRelocation`].
+    ///
+    /// Their format depends on the symbol, none of them are base relocations.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns an iterator over the fixups of this [`DynamicRelocation`], if its
+    /// symbol is [`DynamicRelocation::ARM64X`].
+    pub fn arm64x_fixups(&self) -> Option<Arm64XFixups<'a>> {
+        (self.symbol == Self::ARM64X).then(|| Arm64XFixups {
+            blocks: RelocationTable::new(
+                self.data,
+                &DataDirectory { addr: 0, size: 0 },
+            ),
+            block: None,
+        })
+    }
+}
+
+/// Kind of an [`Arm64XFixup`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arm64XFixupKind<'a> {
+    /// Zero the specified number of bytes
+    ZeroFill(u8),
+    /// Overwrite the bytes with the value, which is 1, 2, 4 or 8 bytes long
+    Value(&'a [u8]),
+    /// Add the delta to the 64-bit value
+    Delta(i64),
+}
+
+/// Fixup applied to an ARM64X image by the loader
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Arm64XFixup<'a> {
+    /// RVA the fixup is applied to, which is the page RVA plus the offset
+    pub rva: u32,
+    /// Type of the fixup
+    pub kind: Arm64XFixupKind<'a>,
+}
+
+/// Iterator over the fixups of an ARM64X [`DynamicRelocation`]
+///
+/// The fixups are grouped into blocks framed like the blocks of the
+/// [`RelocationTable`], but each record may be followed by its value.
+pub struct Arm64XFixups<'a> {
+    blocks: RelocationTable<'a>,
+    block: Option<(u32, ByteReader<'a>)>,
+}
+
+impl<'a> Arm64XFixups<'a> {
+    const TYPE_ZERO_FILL: u16 = 0;
+    const TYPE_VALUE: u16 = 1;
+    const TYPE_DELTA: u16 = 2;
+
+    /// Internal method for decoding the record at the start of the specified block.
+    fn read_fixup(
+        page_rva: u32,
+        data: &mut ByteReader<'a>,
+    ) -> Result<Arm64XFixup<'a>> {
+        let record = data.read_copied::<u16>()?;
+        let rva = page_rva.wrapping_add(record as u32 & 0xFFF);
+
+        let kind = match (record >> 12) & 0x3 {
+            Self::TYPE_ZERO_FILL => Arm64XFixupKind::ZeroFill(1 << (record >> 14)),
+            Self::TYPE_VALUE => {
+                let size = 1 << (record >> 14);
+                let value = data.remaining_bytes().get(..size).ok_or_else(|| {
+                    Error::Malformed(format!(
+                        "arm64x fixup value exceeds its block ({rva:#x})"
+                    ))
+                })?;
+                data.skip_to(Pos::Rel(size));
+                Arm64XFixupKind::Value(value)
+            }
+            Self::TYPE_DELTA => {
+                let value = data.read_copied::<u16>().map_err(|_| {
+                    Error::Malformed(format!(
+                        "arm64x fixup delta exceeds its block ({rva:#x})"
+                    ))
+                })?;
+
+                // The delta is scaled by 4 or 8 and may be negative
+                let scale = if record & 0x8000 != 0 { 8 } else { 4 };
+                let delta = value as i64 * scale;
+                Arm64XFixupKind::Delta(if record & 0x4000 != 0 {
+                    -delta
+                } else {
+                    delta
+                })
+            }
+            v => {
+                return Err(Error::Malformed(format!(
+                    "arm64x fixup has unknown type ({v})"
+                )))
+            }
+        };
+
+        Ok(Arm64XFixup { rva, kind })
+    }
+}
+
+impl<'a> Iterator for Arm64XFixups<'a> {
+    type Item = Result<Arm64XFixup<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some((page_rva, data)) = &mut self.block else {
+                match self.blocks.next()? {
+                    Ok(block) => {
+                        self.block =
+                            Some((block.page_rva(), ByteReader::new(block.bytes())));
+                        continue;
+                    }
+                    Err(e) => return Some(Err(e)),
+                }
+            };
+
+            // Blocks are padded to a 4 byte boundary with a null record
+            match data.remaining_bytes() {
+                [] | [0, 0] => {
+                    self.block = None;
+                    continue;
+                }
+                _ => {}
+            }
+
+            match Self::read_fixup(*page_rva, data) {
+                Ok(v) => return Some(Ok(v)),
+                Err(e) => {
+                    // Skip the block, since the following records can not be located
+                    self.block = None;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for Arm64XFixups<'a> {}
+
+/// Iterator over the dynamic value relocation table referenced by a [`LoadConfig`]
+///
+/// Only version 1 of the table is supported.
+pub struct DynamicRelocationTable<'a> {
+    data: ByteReader<'a>,
+}
+
+impl<'a> DynamicRelocationTable<'a> {
+    /// Parses the [`DynamicRelocationTable`] at the start of the specified bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the head of the table is outside of
+    /// the bytes, or its version is not supported.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let mut data = ByteReader::new(bytes);
+        let head = data.read_copied::<DynamicRelocationTableHead>()?;
+        if head.version != 1 {
+            return Error::make_malformed::<DynamicRelocationTableHead, _>(format!(
+                "has unsupported version ({})",
+                head.version
+            ));
+        }
+
+        let bytes = data
+            .remaining_bytes()
+            .get(..head.size as usize)
+            .ok_or(Error::InsufficientBufferAt(mem::size_of_val(&head)))?;
+
+        Ok(Self {
+            data: ByteReader::new(bytes),
+        })
+    }
+}
+
+impl<'a> Iterator for DynamicRelocationTable<'a> {
+    type Item = Result<DynamicRelocation<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (|| -> Result<_> {
+            let symbol = self.data.read_copied::<u64>()?;
+            let size = self.data.read_copied::<u32>()?;
+            let data = self
+                .data
+                .remaining_bytes()
+                .get(..size as usize)
+                .ok_or_else(|| {
+                    Error::Malformed(format!(
+                        "dynamic relocation exceeds its table ({size:#x})"
+                    ))
+                })?;
+            self.data.skip_to(Pos::Rel(data.len()));
+
+            Ok(DynamicRelocation { symbol, data })
+        })() {
+            Ok(v) => Some(Ok(v)),
+            Err(e) if e.is_insufficient_buffer() => None,
+            Err(e) => {
+                // Stop iterating, since the following entry can not be located
+                self.data
+                    .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for DynamicRelocationTable<'a> {}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-image_load_config_directory64)
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct LoadConfigDirectory {
+    pub size: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub global_flags_clear: u32,
+    pub global_flags_set: u32,
+    pub critical_section_default_timeout: u32,
+    pub decommit_free_block_threshold: u64,
+    pub decommit_total_free_threshold: u64,
+    pub lock_prefix_table: u64,
+    pub maximum_allocation_size: u64,
+    pub virtual_memory_threshold: u64,
+    pub process_affinity_mask: u64,
+    pub process_heap_flags: u32,
+    pub csd_version: u16,
+    pub dependent_load_flags: u16,
+    pub edit_list: u64,
+    pub security_cookie: u64,
+    pub se_handler_table: u64,
+    pub se_handler_count: u64,
+    pub guard_cf_check_function_pointer: u64,
+    pub guard_cf_dispatch_function_pointer: u64,
+    pub guard_cf_function_table: u64,
+    pub guard_cf_function_count: u64,
+    pub guard_flags: u32,
+    pub code_integrity_flags: u16,
+    pub code_integrity_catalog: u16,
+    pub code_integrity_catalog_offset: u32,
+    pub code_integrity_reserved: u32,
+    pub guard_address_taken_iat_entry_table: u64,
+    pub guard_address_taken_iat_entry_count: u64,
+    pub guard_long_jump_target_table: u64,
+    pub guard_long_jump_target_count: u64,
+    pub dynamic_value_reloc_table: u64,
+    pub chpe_metadata_pointer: u64,
+    pub guard_rf_failure_routine: u64,
+    pub guard_rf_failure_routine_function_pointer: u64,
+    pub dynamic_value_reloc_table_offset: u32,
+    pub dynamic_value_reloc_table_section: u16,
+    pub reserved2: u16,
+    pub guard_rf_verify_stack_pointer_function_pointer: u64,
+    pub hot_patch_table_offset: u32,
+    pub reserved3: u32,
+    pub enclave_configuration_pointer: u64,
+    pub volatile_metadata_pointer: u64,
+    pub guard_eh_continuation_table: u64,
+    pub guard_eh_continuation_count: u64,
+    pub guard_xfg_check_function_pointer: u64,
+    pub guard_xfg_dispatch_function_pointer: u64,
+    pub guard_xfg_table_dispatch_function_pointer: u64,
+    pub cast_guard_os_determined_failure_mode: u64,
+    pub guard_memcpy_function_pointer: u64,
+}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-image_load_config_directory64),
+/// see `IMAGE_DYNAMIC_RELOCATION_TABLE`
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct DynamicRelocationTableHead {
+    pub version: u32,
+    pub size: u32,
+}
+
+impl_from_bytes!(LoadConfigDirectory, DynamicRelocationTableHead);
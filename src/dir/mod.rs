@@ -1,3 +1,5 @@
+use crate::{impl_from_bytes, mem::FromBytes};
+
 mod relocation;
 pub use relocation::*;
 mod import;
@@ -6,28 +8,74 @@ mod export;
 pub use export::*;
 mod cert;
 pub use cert::*;
+mod resource;
+pub use resource::*;
+mod version;
+pub use version::*;
+mod debug;
+pub use debug::*;
+mod load_config;
+pub use load_config::*;
+mod tls;
+pub use tls::*;
 
-#[derive(Clone, Copy)]
+/// Type of a data directory, with its index in the optional header as discriminant
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DataDirectoryType {
-    ExportTable,
-    ImportTable,
-    ResourceTable,
-    ExceptionTable,
-    CertificateTable,
-    RelocationTable,
-    Debug,
-    Architecture,
-    GlobalPointer,
-    TLSTable,
-    LoadConfigTable,
-    BoundImportTable,
-    ImportAddressTable,
-    DelayImportDescriptor,
-    CLRRuntimeHeader,
-    Reserved,
+    ExportTable = 0,
+    ImportTable = 1,
+    ResourceTable = 2,
+    ExceptionTable = 3,
+    /// Attribute certificate table, whose data directory holds a file offset
+    /// instead of an RVA, see [`crate::file::PeView::certificate_data`]
+    CertificateTable = 4,
+    RelocationTable = 5,
+    Debug = 6,
+    Architecture = 7,
+    GlobalPointer = 8,
+    TLSTable = 9,
+    LoadConfigTable = 10,
+    BoundImportTable = 11,
+    ImportAddressTable = 12,
+    DelayImportDescriptor = 13,
+    CLRRuntimeHeader = 14,
+    Reserved = 15,
+}
+
+impl DataDirectoryType {
+    /// Every [`DataDirectoryType`], ordered by its index in the optional header
+    pub const ALL: [Self; 16] = [
+        Self::ExportTable,
+        Self::ImportTable,
+        Self::ResourceTable,
+        Self::ExceptionTable,
+        Self::CertificateTable,
+        Self::RelocationTable,
+        Self::Debug,
+        Self::Architecture,
+        Self::GlobalPointer,
+        Self::TLSTable,
+        Self::LoadConfigTable,
+        Self::BoundImportTable,
+        Self::ImportAddressTable,
+        Self::DelayImportDescriptor,
+        Self::CLRRuntimeHeader,
+        Self::Reserved,
+    ];
+
+    /// Returns the [`DataDirectoryType`] at the specified index in the optional header.
+    ///
+    /// Returns [`None`] if the index is out of range.
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
 }
 
 /// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#optional-header-data-directories-image-only)
+///
+/// Following the naming of [`crate::file::PeAddr`], `addr` is an address rather than
+/// strictly an RVA: it is an RVA for every directory except
+/// [`DataDirectoryType::CertificateTable`], for which it is a file offset.
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct DataDirectory {
@@ -37,8 +85,12 @@ pub struct DataDirectory {
 
 impl DataDirectory {
     /// Checks if the specified RVA is within the bounds of this [`DataDirectory`]
+    ///
+    /// Empty directories and directories whose end overflows contain nothing.
     pub fn contains_addr(&self, addr: u32) -> bool {
-        (self.addr..self.addr + self.size).contains(&addr)
+        self.addr
+            .checked_add(self.size)
+            .is_some_and(|end| (self.addr..end).contains(&addr))
     }
 }
 
@@ -48,3 +100,5 @@ pub trait DataDirectoryTable<'a> {
     /// Returns the [`DataDirectoryType`] of this table
     fn typ() -> DataDirectoryType;
 }
+
+impl_from_bytes!(DataDirectory);
@@ -1,20 +1,57 @@
 use crate::{dir::*, error::*, impl_from_bytes, mem::*};
-use alloc::format;
-use core::mem;
+use alloc::{format, vec::Vec};
+use core::{iter::FusedIterator, mem};
 
-/// Relocation entry of a relocation block
+/// Type of a relocation
 /// Values are defined by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#base-relocation-types)
-pub enum Relocation {
-    Absolute(u16),
-    High(u16),
-    Low(u16),
-    HighLow(u16),
-    HighAdj(u16),
-    MipsArmRiscv(u16),
-    ThumbRiscv(u16),
-    RiscvLoong(u16),
-    JmpAddr(u16),
-    Dir64(u16),
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum RelocationKind {
+    Absolute = 0x0,
+    High = 0x1,
+    Low = 0x2,
+    HighLow = 0x3,
+    HighAdj = 0x4,
+    MipsArmRiscv = 0x5,
+    ThumbRiscv = 0x7,
+    RiscvLoong = 0x8,
+    JmpAddr = 0x9,
+    Dir64 = 0xA,
+    /// Reserved or architecture specific type, holding its raw value
+    Unknown(u16),
+}
+
+impl RelocationKind {
+    /// Returns the raw type value of this [`RelocationKind`],
+    /// as stored in the upper four bits of a [`RelocationEntry`].
+    pub fn code(&self) -> u16 {
+        use RelocationKind::*;
+
+        match *self {
+            Absolute => 0x0,
+            High => 0x1,
+            Low => 0x2,
+            HighLow => 0x3,
+            HighAdj => 0x4,
+            MipsArmRiscv => 0x5,
+            ThumbRiscv => 0x7,
+            RiscvLoong => 0x8,
+            JmpAddr => 0x9,
+            Dir64 => 0xA,
+            Unknown(kind) => kind,
+        }
+    }
+}
+
+/// Relocation entry of a relocation block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Relocation {
+    /// Type of the relocation
+    pub kind: RelocationKind,
+    /// Offset of the relocation within the page of its block
+    pub offset: u16,
+    /// RVA the relocation is applied to, which is the page RVA plus the offset
+    pub rva: u32,
 }
 
 /// Iterator over the entries of a single relocation block
@@ -36,10 +73,27 @@ impl<'a> RelocationBlock<'a> {
         self.head.page_rva
     }
 
+    /// Returns the remaining raw entries of this [`RelocationBlock`].
+    pub fn bytes(&self) -> &'a [u8] {
+        self.data.remaining_bytes()
+    }
+
     /// Returns the number of entries in this [`RelocationBlock`].
+    ///
+    /// Blocks too small to hold their own head have no entries.
     pub fn entry_count(&self) -> usize {
-        (self.head.block_size as usize - mem::size_of::<RelocationHead>())
-            / mem::size_of::<RelocationEntry>()
+        (self.head.block_size as usize)
+            .checked_sub(mem::size_of::<RelocationHead>())
+            .map_or(0, |size| size / mem::size_of::<RelocationEntry>())
+    }
+
+    /// Drains this [`RelocationBlock`] into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<Relocation>> {
+        self.collect()
     }
 }
 
@@ -47,23 +101,57 @@ impl<'a> Iterator for RelocationBlock<'a> {
     type Item = Result<Relocation>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match (|| {
+        match (|| -> Result<_> {
             // Read and convert the next BR entry
             let entry = self.data.read::<RelocationEntry>()?;
-            Relocation::try_from(entry)
+
+            Ok(Relocation {
+                kind: RelocationKind::from(entry),
+                offset: entry.offset(),
+                rva: self.head.page_rva.wrapping_add(entry.offset() as u32),
+            })
         })() {
             Ok(v) => Some(Ok(v)),
-            Err(Error::InsufficientBuffer) => None,
-            Err(e) => Some(Err(e)),
+            Err(e) if e.is_insufficient_buffer() => None,
+            Err(e) => {
+                self.data
+                    .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+                Some(Err(e))
+            }
         }
     }
 }
 
+impl<'a> FusedIterator for RelocationBlock<'a> {}
+
 /// Iterator over the relocation table located in .reloc
 pub struct RelocationTable<'a> {
     data: ByteReader<'a>,
 }
 
+impl<'a> RelocationTable<'a> {
+    /// Drains this [`RelocationTable`] into a [`Vec`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while iterating
+    pub fn collect_all(self) -> Result<Vec<RelocationBlock<'a>>> {
+        self.collect()
+    }
+
+    /// Returns an iterator over the blocks of this [`RelocationTable`] in reverse order.
+    ///
+    /// Since blocks are prefixed by their size, they can only be located from the
+    /// start of the table. All blocks are therefore collected in a forward pass first.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while collecting the blocks
+    pub fn rev(self) -> Result<impl Iterator<Item = RelocationBlock<'a>>> {
+        Ok(self.collect_all()?.into_iter().rev())
+    }
+}
+
 impl<'a> DataDirectoryTable<'a> for RelocationTable<'a> {
     fn new(bytes: &'a [u8], _dir: &'a DataDirectory) -> Self {
         Self {
@@ -85,23 +173,49 @@ impl<'a> Iterator for RelocationTable<'a> {
             Ok(head) => {
                 // Check if we reached the end of the table
                 if head.block_size == 0
-                    || head.block_size as usize % mem::size_of::<u32>() != 0
+                    || !(head.block_size as usize)
+                        .is_multiple_of(mem::size_of::<u32>())
                 {
+                    self.data
+                        .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
                     return None;
                 }
 
-                let data = &self.data.remaining_bytes()
-                    [..head.block_size as usize - mem::size_of::<RelocationHead>()];
+                // The block must at least hold its head and may not exceed the table
+                let data = match (head.block_size as usize)
+                    .checked_sub(mem::size_of::<RelocationHead>())
+                    .and_then(|size| self.data.remaining_bytes().get(..size))
+                {
+                    Some(v) => v,
+                    None => {
+                        // Stop iterating, since the following block can not be located
+                        self.data
+                            .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+
+                        return Some(Error::make_malformed::<RelocationHead, _>(
+                            format!(
+                                "has invalid block size ({:#x})",
+                                head.block_size
+                            ),
+                        ));
+                    }
+                };
                 self.data.skip_to(Pos::Rel(data.len()));
 
                 Some(Ok(RelocationBlock::new(data, head)))
             }
-            Err(Error::InsufficientBuffer) => None,
-            Err(e) => Some(Err(e)),
+            Err(e) if e.is_insufficient_buffer() => None,
+            Err(e) => {
+                self.data
+                    .skip_to(Pos::Rel(self.data.remaining_bytes().len()));
+                Some(Err(e))
+            }
         }
     }
 }
 
+impl<'a> FusedIterator for RelocationTable<'a> {}
+
 /// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#base-relocation-block)
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -124,30 +238,23 @@ impl RelocationEntry {
     }
 }
 
-impl TryFrom<&RelocationEntry> for Relocation {
-    type Error = Error;
-
-    fn try_from(value: &RelocationEntry) -> core::result::Result<Self, Self::Error> {
-        use Relocation::*;
-
-        Ok(match value.kind() {
-            0x0 => Absolute(value.offset()),
-            0x1 => High(value.offset()),
-            0x2 => Low(value.offset()),
-            0x3 => HighLow(value.offset()),
-            0x4 => HighAdj(value.offset()),
-            0x5 => MipsArmRiscv(value.offset()),
-            0x7 => ThumbRiscv(value.offset()),
-            0x8 => RiscvLoong(value.offset()),
-            0x9 => JmpAddr(value.offset()),
-            0xA => Dir64(value.offset()),
-            _ => {
-                return Error::make_malformed::<RelocationEntry, _>(format!(
-                    "has invalid type ({})",
-                    value.kind()
-                ))
-            }
-        })
+impl From<&RelocationEntry> for RelocationKind {
+    fn from(value: &RelocationEntry) -> Self {
+        use RelocationKind::*;
+
+        match value.kind() {
+            0x0 => Absolute,
+            0x1 => High,
+            0x2 => Low,
+            0x3 => HighLow,
+            0x4 => HighAdj,
+            0x5 => MipsArmRiscv,
+            0x7 => ThumbRiscv,
+            0x8 => RiscvLoong,
+            0x9 => JmpAddr,
+            0xA => Dir64,
+            kind => Unknown(kind),
+        }
     }
 }
 
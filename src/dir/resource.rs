@@ -0,0 +1,203 @@
+use crate::{dir::*, error::*, impl_from_bytes, mem::*};
+use alloc::format;
+
+/// Resource type of the version information, see [`crate::file::PeView::version_info`]
+pub const RT_VERSION: u16 = 16;
+
+/// Node of the resource tree, which is either a directory or a leaf holding the data
+pub enum ResourceNode<'a> {
+    /// Directory containing further nodes
+    Directory(ResourceDirectory<'a>),
+    /// Leaf describing the location of the resource data
+    Data(&'a ResourceDataEntry),
+}
+
+/// Directory of the resource tree
+///
+/// The tree usually consists of three levels of directories, being the type,
+/// name and language of the resources.
+pub struct ResourceDirectory<'a> {
+    head: &'a ResourceDirectoryHead,
+    entries: &'a [ResourceDirectoryEntry],
+    data: ByteReader<'a>,
+}
+
+impl<'a> ResourceDirectory<'a> {
+    /// Parses the [`ResourceDirectory`] at the specified offset within the resource
+    /// table.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory or its entries are
+    /// outside of the tables bounds.
+    pub fn parse(bytes: &'a [u8], offset: u32) -> Result<Self> {
+        let mut data = ByteReader::new(bytes);
+        data.try_skip_to(Pos::Abs(offset as _))?;
+
+        let head = data.read::<ResourceDirectoryHead>()?;
+        let entries = data.read_slice::<ResourceDirectoryEntry>(
+            head.num_of_named_entries as usize + head.num_of_id_entries as usize,
+        )?;
+
+        Ok(Self {
+            head,
+            entries,
+            data: ByteReader::new(bytes),
+        })
+    }
+
+    /// Returns a reference to the head of this [`ResourceDirectory`].
+    pub fn head(&self) -> &'a ResourceDirectoryHead {
+        self.head
+    }
+
+    /// Returns the entries of this [`ResourceDirectory`],
+    /// with the named entries preceding the ones identified by an ID.
+    pub fn entries(&self) -> &'a [ResourceDirectoryEntry] {
+        self.entries
+    }
+
+    /// Returns the entry of this [`ResourceDirectory`] with the specified ID.
+    ///
+    /// Returns [`None`] if no such entry is found.
+    pub fn entry_by_id(&self, id: u16) -> Option<&'a ResourceDirectoryEntry> {
+        self.entries.iter().find(|e| e.id() == Some(id))
+    }
+
+    /// Returns the node the specified entry of this [`ResourceDirectory`] points to.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the node is outside of the tables bounds.
+    pub fn node(&self, entry: &ResourceDirectoryEntry) -> Result<ResourceNode<'a>> {
+        if entry.is_directory() {
+            Ok(ResourceNode::Directory(Self::parse(
+                self.data.bytes(),
+                entry.child_offset(),
+            )?))
+        } else {
+            Ok(ResourceNode::Data(
+                self.data
+                    .read_at::<ResourceDataEntry>(entry.child_offset() as _)?,
+            ))
+        }
+    }
+
+    /// Returns the data entry found by descending into the first entry of each level
+    /// below the specified entry of this [`ResourceDirectory`].
+    ///
+    /// This selects the first name and language of a resource type.
+    /// Returns [`None`] if a directory on the way is empty.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a node is outside of the tables bounds
+    /// or the tree is nested deeper than three levels.
+    pub fn first_data(
+        &self,
+        entry: &ResourceDirectoryEntry,
+    ) -> Result<Option<&'a ResourceDataEntry>> {
+        let mut node = self.node(entry)?;
+
+        // The entry itself is on the first level, so at most two directories follow
+        for _ in 0..2 {
+            node = match node {
+                ResourceNode::Directory(dir) => match dir.entries.first() {
+                    Some(e) => dir.node(e)?,
+                    None => return Ok(None),
+                },
+                data => data,
+            };
+        }
+
+        match node {
+            ResourceNode::Data(data) => Ok(Some(data)),
+            ResourceNode::Directory(_) => Error::make_malformed::<Self, _>(format!(
+                "is nested too deeply below entry ({:#x})",
+                entry.name
+            )),
+        }
+    }
+}
+
+/// Resource table located in .rsrc
+pub struct ResourceTable<'a> {
+    data: ByteReader<'a>,
+}
+
+impl<'a> ResourceTable<'a> {
+    /// Returns the root [`ResourceDirectory`] of this [`ResourceTable`],
+    /// whose entries are the types of the resources.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the root directory is malformed.
+    pub fn root(&self) -> Result<ResourceDirectory<'a>> {
+        ResourceDirectory::parse(self.data.bytes(), 0)
+    }
+}
+
+impl<'a> DataDirectoryTable<'a> for ResourceTable<'a> {
+    fn new(bytes: &'a [u8], _dir: &'a DataDirectory) -> Self {
+        Self {
+            data: ByteReader::new(bytes),
+        }
+    }
+
+    fn typ() -> DataDirectoryType {
+        DataDirectoryType::ResourceTable
+    }
+}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#resource-directory-table)
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct ResourceDirectoryHead {
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub num_of_named_entries: u16,
+    pub num_of_id_entries: u16,
+}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#resource-directory-entries)
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct ResourceDirectoryEntry {
+    pub name: u32,
+    pub offset: u32,
+}
+
+impl ResourceDirectoryEntry {
+    /// Returns the ID of the entry, or [`None`] if it is identified by a name.
+    pub fn id(&self) -> Option<u16> {
+        (self.name & 0x8000_0000 == 0).then_some(self.name as u16)
+    }
+
+    /// Checks if the entry points to another [`ResourceDirectory`].
+    pub fn is_directory(&self) -> bool {
+        self.offset & 0x8000_0000 != 0
+    }
+
+    /// Returns the offset of the node the entry points to within the resource table.
+    pub fn child_offset(&self) -> u32 {
+        self.offset & 0x7FFF_FFFF
+    }
+}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#resource-data-entry)
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct ResourceDataEntry {
+    pub data_rva: u32,
+    pub size: u32,
+    pub code_page: u32,
+    pub reserved: u32,
+}
+
+impl_from_bytes!(
+    ResourceDirectoryHead,
+    ResourceDirectoryEntry,
+    ResourceDataEntry
+);
@@ -0,0 +1,127 @@
+use crate::{
+    error::*,
+    file::{PeAddr, PeView},
+    impl_from_bytes,
+    mem::*,
+    section::Section,
+};
+use alloc::format;
+use core::iter;
+
+/// Thread local storage directory of an image
+pub struct Tls {
+    directory: TlsDirectory,
+}
+
+impl Tls {
+    /// Parses the [`Tls`] directory at the start of the specified bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the bytes are too small to hold
+    /// a [`TlsDirectory`].
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            directory: TlsDirectory::from_bytes_copied(bytes)?,
+        })
+    }
+
+    /// Returns a reference to the [`TlsDirectory`] of this [`Tls`].
+    pub fn directory(&self) -> &TlsDirectory {
+        &self.directory
+    }
+
+    /// Returns an iterator over the virtual addresses of the TLS callbacks, which are
+    /// called by the loader before the entry point of the specified image.
+    ///
+    /// The callback array is null-terminated and read from the raw data of the section
+    /// containing it. Iterating stops after the first error.
+    pub fn callbacks<'b>(
+        &self,
+        pe: &'b PeView<'_>,
+    ) -> impl Iterator<Item = Result<u64>> + 'b {
+        let va = self.directory.address_of_callbacks;
+
+        // Locate the callback array, deferring any error to the first iteration
+        let located = match va {
+            0 => Ok(ByteReader::new(&[])),
+            _ => pe
+                .va_to_rva(va)
+                .ok_or_else(|| {
+                    Error::Malformed(format!(
+                        "tls callbacks are outside of the image ({va:#x})"
+                    ))
+                })
+                .and_then(|rva| {
+                    pe.section_by_addr(PeAddr::Rva(rva))
+                        .ok_or(Error::SectionEmpty)?
+                        .bytes_at_rva(rva)
+                })
+                .map(ByteReader::new),
+        };
+        let (mut data, mut error) = match located {
+            Ok(data) => (data, None),
+            Err(e) => (ByteReader::new(&[]), Some(e)),
+        };
+
+        let mut done = va == 0;
+        iter::from_fn(move || {
+            if done {
+                return None;
+            }
+
+            let entry = match error.take() {
+                Some(e) => Err(e),
+                None => data.read_copied::<u64>(),
+            };
+
+            // Stop at the null terminator or the first error
+            match entry {
+                Ok(0) => {
+                    done = true;
+                    None
+                }
+                Ok(v) => Some(Ok(v)),
+                Err(e) => {
+                    done = true;
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+
+    /// Returns an iterator over the TLS callbacks of the specified image, resolved
+    /// to the section containing each callback and the offset within it.
+    ///
+    /// Callbacks are commonly used to run code before the entry point, so they are
+    /// worth locating during analysis. Iterating stops after the first error.
+    pub fn callbacks_located<'b>(
+        &self,
+        pe: &'b PeView<'_>,
+    ) -> impl Iterator<Item = Result<(&'b Section<'b>, u32)>> + 'b {
+        self.callbacks(pe).map(|va| {
+            let va = va?;
+            pe.va_to_rva(va)
+                .and_then(|rva| pe.section_and_offset(rva))
+                .ok_or_else(|| {
+                    Error::Malformed(format!(
+                        "tls callback is outside of any section ({va:#x})"
+                    ))
+                })
+        })
+    }
+}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#the-tls-directory)
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct TlsDirectory {
+    pub start_address_of_raw_data: u64,
+    pub end_address_of_raw_data: u64,
+    pub address_of_index: u64,
+    pub address_of_callbacks: u64,
+    pub size_of_zero_fill: u32,
+    pub characteristics: u32,
+}
+
+impl_from_bytes!(TlsDirectory);
@@ -0,0 +1,228 @@
+use crate::{error::*, impl_from_bytes, mem::*};
+use alloc::{format, string::String, vec::Vec};
+use core::mem;
+
+/// Version information of an image, stored as its `RT_VERSION` resource
+///
+/// See [`crate::file::PeView::version_info`].
+pub struct VersionInfo {
+    fixed: Option<FixedFileInfo>,
+    strings: Vec<(String, String)>,
+}
+
+impl VersionInfo {
+    /// Parses the specified `VS_VERSIONINFO` structure.
+    ///
+    /// Only the `StringFileInfo` child is read, `VarFileInfo` is skipped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the structure or one of its nested
+    /// blocks is malformed.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let root = VersionBlock::parse(bytes)?;
+        if root.key != "VS_VERSION_INFO" {
+            return Error::make_malformed::<Self, _>(format!(
+                "has invalid key ({})",
+                root.key
+            ));
+        }
+
+        // The fixed file info is optional and signals its presence by its length
+        let fixed = if root.value.is_empty() {
+            None
+        } else {
+            Some(*FixedFileInfo::from_bytes_copied(root.value)?.validate()?)
+        };
+
+        // StringFileInfo holds one table of strings per language and code page
+        let mut strings = Vec::new();
+        for block in VersionBlock::children(root.children) {
+            let block = block?;
+            if block.key != "StringFileInfo" {
+                continue;
+            }
+
+            for table in VersionBlock::children(block.children) {
+                for string in VersionBlock::children(table?.children) {
+                    let string = string?;
+                    let value = if string.value.is_empty() {
+                        String::new()
+                    } else {
+                        utf16_from_bytes(string.value_and_rest)?
+                    };
+
+                    strings.push((string.key, value));
+                }
+            }
+        }
+
+        Ok(Self { fixed, strings })
+    }
+
+    /// Returns the `VS_FIXEDFILEINFO` of this [`VersionInfo`], if present.
+    pub fn fixed(&self) -> Option<&FixedFileInfo> {
+        self.fixed.as_ref()
+    }
+
+    /// Returns the key-value pairs of every string table in `StringFileInfo`,
+    /// like `CompanyName` or `OriginalFilename`.
+    pub fn strings(&self) -> &[(String, String)] {
+        &self.strings
+    }
+
+    /// Returns the value of the first string with the specified key.
+    ///
+    /// Returns [`None`] if no such string is found.
+    pub fn string(&self, key: &str) -> Option<&str> {
+        self.strings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Block of the `VS_VERSIONINFO` structure, which is nested by all of its children
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    value_and_rest: &'a [u8],
+    children: &'a [u8],
+    length: usize,
+}
+
+impl<'a> VersionBlock<'a> {
+    /// Parses the block at the start of the specified bytes.
+    fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let head = VersionBlockHead::from_bytes_copied(bytes)?;
+
+        // The block must at least hold its head and may not exceed its parent
+        let Some(bytes) = bytes
+            .get(..head.length as usize)
+            .filter(|b| b.len() >= mem::size_of::<VersionBlockHead>())
+        else {
+            return Error::make_malformed::<VersionBlockHead, _>(format!(
+                "has invalid length ({:#x})",
+                head.length
+            ));
+        };
+
+        // The key is followed by padding to a 4 byte boundary
+        let key = utf16_from_bytes(&bytes[mem::size_of::<VersionBlockHead>()..])?;
+        let value_offset = align_up(
+            mem::size_of::<VersionBlockHead>()
+                + (key.encode_utf16().count() + 1) * 2,
+            4,
+        );
+
+        // The length of text values is counted in words
+        let value_size = match head.typ {
+            1 => head.value_length as usize * 2,
+            _ => head.value_length as usize,
+        };
+
+        let value_and_rest = bytes.get(value_offset..).unwrap_or_default();
+        let value = value_and_rest
+            .get(..value_size)
+            .ok_or(Error::InsufficientBuffer)?;
+        let children = bytes
+            .get(align_up(value_offset + value_size, 4)..)
+            .unwrap_or_default();
+
+        Ok(Self {
+            key,
+            value,
+            value_and_rest,
+            children,
+            length: bytes.len(),
+        })
+    }
+
+    /// Returns an iterator over the blocks contained in the specified bytes,
+    /// which are each aligned to a 4 byte boundary.
+    fn children(mut bytes: &'a [u8]) -> impl Iterator<Item = Result<Self>> {
+        core::iter::from_fn(move || {
+            if bytes.is_empty() {
+                return None;
+            }
+
+            // Stop iterating on errors, since the following block can not be located
+            let block = Self::parse(bytes);
+            bytes = match &block {
+                Ok(b) => bytes.get(align_up(b.length, 4)..).unwrap_or_default(),
+                Err(_) => &[],
+            };
+
+            Some(block)
+        })
+    }
+}
+
+/// Head of every block within the `VS_VERSIONINFO` structure
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct VersionBlockHead {
+    length: u16,
+    value_length: u16,
+    typ: u16,
+}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/verrsrc/ns-verrsrc-vs_fixedfileinfo)
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct FixedFileInfo {
+    pub signature: u32,
+    pub struc_version: u32,
+    pub file_version_ms: u32,
+    pub file_version_ls: u32,
+    pub product_version_ms: u32,
+    pub product_version_ls: u32,
+    pub file_flags_mask: u32,
+    pub file_flags: u32,
+    pub file_os: u32,
+    pub file_type: u32,
+    pub file_subtype: u32,
+    pub file_date_ms: u32,
+    pub file_date_ls: u32,
+}
+
+impl FixedFileInfo {
+    pub const SIGNATURE: u32 = 0xFEEF04BD;
+
+    /// Validates the signature of this [`FixedFileInfo`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::Validation`] if the signature is invalid.
+    pub fn validate(&self) -> Result<&Self> {
+        if self.signature != Self::SIGNATURE {
+            return Error::make_validation::<Self, _>(ValidationRule::Signature(
+                self.signature,
+            ));
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the file version as its four components, most significant first.
+    pub fn file_version(&self) -> [u16; 4] {
+        split_version(self.file_version_ms, self.file_version_ls)
+    }
+
+    /// Returns the product version as its four components, most significant first.
+    pub fn product_version(&self) -> [u16; 4] {
+        split_version(self.product_version_ms, self.product_version_ls)
+    }
+
+    /// Returns the `file_flags` field, masked by the `file_flags_mask` field.
+    pub fn flags(&self) -> u32 {
+        self.file_flags & self.file_flags_mask
+    }
+}
+
+/// Splits the two halves of a version into its four components
+fn split_version(ms: u32, ls: u32) -> [u16; 4] {
+    [(ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16]
+}
+
+impl_from_bytes!(VersionBlockHead, FixedFileInfo);
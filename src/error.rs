@@ -1,3 +1,4 @@
+use crate::dir::DataDirectoryType;
 use alloc::{format, string::String};
 use core::{
     any, error,
@@ -10,11 +11,25 @@ pub type Result<T> = result::Result<T, Error>;
 #[derive(Debug)]
 pub enum Error {
     Misaligned,
+    MisalignedAt(usize),
     InsufficientBuffer,
+    InsufficientBufferAt(usize),
     Malformed(String),
+    /// Native structure violates a fixed rule of the specification
+    Validation {
+        /// Type name of the structure
+        structure: &'static str,
+        /// Rule which is violated
+        rule: ValidationRule,
+    },
     InvalidFileFormat,
     DataDirectoryEmpty,
+    /// Data directory of the type holds no data which could be read
+    UnsupportedDirectory(DataDirectoryType),
     SectionEmpty,
+    UninitializedData,
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
 }
 
 impl Error {
@@ -22,21 +37,178 @@ impl Error {
         let type_name = any::type_name::<T>();
         Err(Self::Malformed(format!("{type_name} {m}")))
     }
+
+    pub fn make_validation<T, R>(rule: ValidationRule) -> Result<R> {
+        Err(Self::Validation {
+            structure: any::type_name::<T>(),
+            rule,
+        })
+    }
+
+    /// Attaches the specified position to this [`Error`], if it is positional.
+    pub fn at(self, pos: usize) -> Self {
+        match self {
+            Self::Misaligned => Self::MisalignedAt(pos),
+            Self::InsufficientBuffer => Self::InsufficientBufferAt(pos),
+            e => e,
+        }
+    }
+
+    /// Checks if this [`Error`] is caused by an insufficient buffer, regardless of its position.
+    pub fn is_insufficient_buffer(&self) -> bool {
+        matches!(
+            self,
+            Self::InsufficientBuffer | Self::InsufficientBufferAt(_)
+        )
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let msg = match self {
-            Self::Misaligned => "provided buffer is misaligned",
-            Self::InsufficientBuffer => "provided buffer is too small",
+            Self::Misaligned | Self::MisalignedAt(_) => {
+                "provided buffer is misaligned"
+            }
+            Self::InsufficientBuffer | Self::InsufficientBufferAt(_) => {
+                "provided buffer is too small"
+            }
             Self::Malformed(m) => m,
+            Self::Validation { structure, rule } => {
+                return write!(f, "{structure} {rule}");
+            }
             Self::InvalidFileFormat => "only x64 (PE32+) files are supported",
             Self::DataDirectoryEmpty => "required data directory is empty",
+            Self::UnsupportedDirectory(_) => {
+                "requested data directory is not supported"
+            }
             Self::SectionEmpty => "required section has no raw data",
+            Self::UninitializedData => {
+                "required data is zero-filled by the loader and not part of the file"
+            }
+            #[cfg(feature = "std")]
+            Self::Io(_) => "reading the file failed",
         };
 
-        write!(f, "Error ({self:?}) {msg}")
+        match self {
+            Self::MisalignedAt(pos) | Self::InsufficientBufferAt(pos) => {
+                write!(f, "Error ({self:?}) {msg} at {pos:#x}")
+            }
+            _ => write!(f, "Error ({self:?}) {msg}"),
+        }
     }
 }
 
 impl error::Error for Error {}
+
+/// Rule of the specification a native structure violates, see [`Error::Validation`]
+///
+/// Rules concerning a single field hold the value of the field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValidationRule {
+    /// Signature or magic number does not match
+    Signature(u32),
+    /// Offset of the NT-header is misaligned or outside of the buffer
+    NewHeaderRva(u32),
+    /// Machine architecture is not supported
+    Machine(u16),
+    /// Number of sections is out of range
+    NumOfSections(u16),
+    /// Characteristics are missing
+    MissingCharacteristics,
+    /// Image base is not a multiple of 64 KB
+    ImageBase(u64),
+    /// Section alignment is smaller than the file alignment
+    SectionAlignment(u32),
+    /// File alignment is out of range or does not match the section alignment
+    FileAlignment(u32),
+    /// Reserved field with the specified name is not zero
+    ReservedField(&'static str),
+    /// Size of the image is not a multiple of the section alignment
+    SizeOfImage(u32),
+    /// Size of the headers is not a multiple of the file alignment
+    SizeOfHeaders(u32),
+    /// Size of the raw data of a section is not a multiple of the file alignment
+    RawDataSize(u32),
+    /// Address of the raw data of a section is not a multiple of the file alignment
+    RawDataAddress(u32),
+    /// Section has neither a size nor an address
+    SectionSizeOrAddress,
+    /// Raw data of a section is outside of the file
+    RawDataOutsideFile,
+    /// Raw data of two sections overlaps
+    OverlappingRawData,
+    /// Virtual ranges of two sections overlap
+    OverlappingVirtualRange,
+    /// Number of names exceeds the number of functions
+    NumOfFuncsOrNames,
+    /// Only one of the name and ordinal tables is present
+    NameOrOrdinalTable,
+    /// Entry point is not within an executable section
+    EntryPoint(u32),
+}
+
+impl Display for ValidationRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Signature(v) => write!(f, "has invalid signature ({v:#x})"),
+            Self::NewHeaderRva(v) => {
+                write!(f, "has invalid new header rva ({v:#08x})")
+            }
+            Self::Machine(v) => {
+                write!(f, "has invalid machine architecture ({v:#04x})")
+            }
+            Self::NumOfSections(v) => {
+                write!(f, "has invalid number of sections ({v})")
+            }
+            Self::MissingCharacteristics => write!(f, "has missing characteristics"),
+            Self::ImageBase(v) => write!(f, "has invalid image base ({v:#016x})"),
+            Self::SectionAlignment(v) => {
+                write!(f, "has invalid section alignment ({v:#08x})")
+            }
+            Self::FileAlignment(v) => {
+                write!(f, "has invalid file alignment ({v:#08x})")
+            }
+            Self::ReservedField(name) => {
+                write!(f, "has non zero reserved field '{name}'")
+            }
+            Self::SizeOfImage(v) => {
+                write!(f, "has invalid size of image ({v:#08x})")
+            }
+            Self::SizeOfHeaders(v) => {
+                write!(f, "has invalid size of headers ({v:#08x})")
+            }
+            Self::RawDataSize(v) => {
+                write!(f, "has invalid size of raw data ({v:#08x})")
+            }
+            Self::RawDataAddress(v) => {
+                write!(f, "has invalid address of raw data ({v:#08x})")
+            }
+            Self::SectionSizeOrAddress => {
+                write!(f, "has invalid section size or address")
+            }
+            Self::RawDataOutsideFile => {
+                write!(f, "has raw data outside of the file")
+            }
+            Self::OverlappingRawData => write!(f, "has overlapping raw data"),
+            Self::OverlappingVirtualRange => {
+                write!(f, "has overlapping virtual range")
+            }
+            Self::NumOfFuncsOrNames => {
+                write!(f, "has invalid number of functions or names")
+            }
+            Self::NameOrOrdinalTable => {
+                write!(f, "has invalid rva to name or ordinal table")
+            }
+            Self::EntryPoint(v) => {
+                write!(f, "has entry point outside of executable code ({v:#08x})")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
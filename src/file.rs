@@ -1,9 +1,19 @@
-use crate::{dir::*, error::*, header::*, mem::*, section::Section};
-use alloc::vec::Vec;
-use core::mem;
+use crate::{
+    dir::*,
+    error::*,
+    header::*,
+    mem::*,
+    section::{Section, SectionDiff, SectionIter, SectionMut, WellKnownSection},
+    symbol::{StringTable, SymbolEntry, SymbolTable},
+};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::{mem, mem::ManuallyDrop, ops::Range, ptr::NonNull};
 
 /// Address that represents a position within a [`PeView`]
-#[derive(Clone, Copy)]
+///
+/// Addresses are ordered by their variant first, so every [`PeAddr::Rva`] is less
+/// than every [`PeAddr::FilePtr`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PeAddr {
     /// Relative virtual address
     Rva(u32),
@@ -11,11 +21,232 @@ pub enum PeAddr {
     FilePtr(u32),
 }
 
+impl PeAddr {
+    /// Returns this [`PeAddr`] moved by the specified number of bytes,
+    /// saturating at the bounds of the 32-bit address space.
+    pub fn offset(self, delta: i64) -> Self {
+        let add = |v: u32| {
+            (v as i64).saturating_add(delta).clamp(0, u32::MAX as i64) as u32
+        };
+
+        match self {
+            Self::Rva(v) => Self::Rva(add(v)),
+            Self::FilePtr(v) => Self::FilePtr(add(v)),
+        }
+    }
+
+    /// Returns the RVA, if this [`PeAddr`] is a [`PeAddr::Rva`].
+    pub fn as_rva(self) -> Option<u32> {
+        match self {
+            Self::Rva(v) => Some(v),
+            Self::FilePtr(_) => None,
+        }
+    }
+
+    /// Returns the file offset, if this [`PeAddr`] is a [`PeAddr::FilePtr`].
+    pub fn as_file_ptr(self) -> Option<u32> {
+        match self {
+            Self::Rva(_) => None,
+            Self::FilePtr(v) => Some(v),
+        }
+    }
+}
+
+/// Returns the file offset of the optional header
+fn optional_header_offset(dos_header: &DosHeader) -> usize {
+    dos_header.e_lfanew as usize + mem::offset_of!(NtHeader, optional_header)
+}
+
+/// Returns the file offset of the first section header
+fn section_headers_offset(dos_header: &DosHeader, nt_header: &NtHeader) -> usize {
+    optional_header_offset(dos_header)
+        + nt_header.file_header.size_of_optional_header as usize
+}
+
+/// Returns the file offset of the `check_sum` field of the optional header
+fn checksum_offset(dos_header: &DosHeader) -> usize {
+    optional_header_offset(dos_header) + mem::offset_of!(OptionalHeader, check_sum)
+}
+
+/// Computes the image checksum of the specified file, treating the checksum field at
+/// the specified offset as zero.
+///
+/// The file is summed up as little-endian 16-bit words with their carries folded back in,
+/// to which the length of the file is added.
+fn compute_checksum(bytes: &[u8], checksum_offset: usize) -> u32 {
+    let field = checksum_offset..checksum_offset + mem::size_of::<u32>();
+
+    let mut sum = 0u32;
+    for (i, word) in bytes.chunks(mem::size_of::<u16>()).enumerate() {
+        // The field is not required to be word-aligned, so it is masked per byte
+        let offset = i * mem::size_of::<u16>();
+        let byte = |j: usize| match field.contains(&(offset + j)) {
+            true => 0,
+            false => word.get(j).copied().unwrap_or(0),
+        };
+
+        sum += u16::from_le_bytes([byte(0), byte(1)]) as u32;
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    ((sum & 0xFFFF) + (sum >> 16)).wrapping_add(bytes.len() as u32)
+}
+
+/// Non-fatal validation check, which can be skipped when parsing a [`PeView`]
+#[derive(Clone, Copy)]
+#[repr(u8)]
+pub enum Check {
+    /// Reserved fields are required to be zero
+    ReservedField = 0x1,
+    /// File alignment and the alignment of raw section data
+    FileAlignment = 0x2,
+    /// Image base is required to be a multiple of 64K
+    ImageBase = 0x4,
+    /// Number of sections is limited to 96 by the loader
+    SectionCount = 0x8,
+}
+
+/// Set of [`Check`]s
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Checks(u8);
+
+impl Checks {
+    /// Set containing no checks
+    pub const NONE: Self = Self(0);
+    /// Set containing every check
+    pub const ALL: Self = Self(
+        Check::ReservedField as u8
+            | Check::FileAlignment as u8
+            | Check::ImageBase as u8
+            | Check::SectionCount as u8,
+    );
+
+    /// Checks if the specified check is contained in this set.
+    pub fn contains(&self, check: Check) -> bool {
+        self.0 & check as u8 != 0
+    }
+
+    /// Adds the specified check to this set.
+    pub fn insert(&mut self, check: Check) {
+        self.0 |= check as u8;
+    }
+
+    /// Checks if this set contains no checks.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Options controlling how strictly a [`PeView`] is validated while parsing
+#[derive(Clone, Copy)]
+pub struct ParseOptions {
+    /// Checks which are ignored instead of failing the parse
+    pub skip: Checks,
+    /// Whether the headers are validated at all
+    ///
+    /// Disabling this only skips checks enforcing the specification,
+    /// the checks required for safely reading the file are always performed.
+    pub validate: bool,
+    /// Whether the buffer holds an image mapped into memory, like a memory dump,
+    /// rather than a file
+    ///
+    /// The sections of a mapped image are located at their RVA instead of the
+    /// address of their raw data.
+    pub mapped: bool,
+    /// Machine architectures which are accepted, any other fails the parse
+    ///
+    /// Defaults to [`FileMachine::ALL`]. Only checked if the headers are validated.
+    pub allowed_machines: &'static [FileMachine],
+}
+
+impl ParseOptions {
+    /// Options which enforce every check.
+    pub const fn strict() -> Self {
+        Self {
+            skip: Checks::NONE,
+            validate: true,
+            mapped: false,
+            allowed_machines: &FileMachine::ALL,
+        }
+    }
+
+    /// Options which ignore all non-fatal checks.
+    pub const fn lenient() -> Self {
+        Self {
+            skip: Checks::ALL,
+            validate: true,
+            mapped: false,
+            allowed_machines: &FileMachine::ALL,
+        }
+    }
+
+    /// Options which skip the validation of the headers entirely.
+    pub const fn unchecked() -> Self {
+        Self {
+            skip: Checks::ALL,
+            validate: false,
+            mapped: false,
+            allowed_machines: &FileMachine::ALL,
+        }
+    }
+
+    /// Internal method for handling a failed check.
+    ///
+    /// Returns the specified error, unless the check is skipped,
+    /// in which case it is recorded in `skipped` instead.
+    pub(crate) fn fail(
+        &self,
+        check: Check,
+        skipped: &mut Checks,
+        err: Result<()>,
+    ) -> Result<()> {
+        if self.skip.contains(check) {
+            skipped.insert(check);
+            Ok(())
+        } else {
+            err
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+/// Structural fingerprint of a PE32+ file, see [`PeView::layout_fingerprint`]
+///
+/// Volatile fields like the timestamp or checksum are not part of the fingerprint,
+/// so two builds of the same source are expected to have equal fingerprints.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LayoutFingerprint {
+    sections: Vec<(String, u32, u32)>,
+    directories: Vec<DataDirectoryType>,
+}
+
+impl LayoutFingerprint {
+    /// Returns the name, characteristics and virtual size of each section,
+    /// in the order of the section table.
+    pub fn sections(&self) -> &[(String, u32, u32)] {
+        &self.sections
+    }
+
+    /// Returns the types of all non-empty data directories.
+    pub fn directories(&self) -> &[DataDirectoryType] {
+        &self.directories
+    }
+}
+
 /// View of a PE32+ file
 pub struct PeView<'a> {
-    dos_header: &'a DosHeader,
-    nt_header: &'a NtHeader,
+    dos_header: DosHeader,
+    nt_header: NtHeader,
     sections: Vec<Section<'a>>,
+    sections_by_rva: Vec<usize>,
+    skipped: Checks,
+    mapped: bool,
+    offset: usize,
     data: ByteReader<'a>,
 }
 
@@ -28,179 +259,1526 @@ impl<'a> PeView<'a> {
     /// This function will return an error if the byte buffer does not
     /// represent a valid and complete PE32+ file.
     pub fn parse(bytes: &'a [u8]) -> Result<Self> {
-        // Create an interface for easily reading the buffer
-        let mut data = ByteReader::new(bytes);
+        Self::parse_with(bytes, &ParseOptions::strict())
+    }
 
-        // Read and validate both the DOS- and NT-header
-        let dos_header = data.read::<DosHeader>()?.validate()?;
-        let nt_header = data
-            .skip_to(Pos::Abs(dos_header.e_lfanew as _))
-            .read::<NtHeader>()?
-            .validate()?;
+    /// Creates a [`PeView`] of a PE32+ file like [`PeView::parse`],
+    /// but ignores all non-fatal checks.
+    ///
+    /// The checks which failed are available through [`PeView::skipped_checks`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the byte buffer does not
+    /// represent a structurally complete PE32+ file.
+    pub fn parse_lenient(bytes: &'a [u8]) -> Result<Self> {
+        Self::parse_with(bytes, &ParseOptions::lenient())
+    }
 
-        // Jump to the RVA of the first section header
-        data.skip_to(Pos::Abs(
-            dos_header.e_lfanew as usize
-                + mem::size_of::<u32>()
-                + mem::size_of::<FileHeader>()
-                + nt_header.file_header.size_of_optional_header as usize,
-        ));
+    /// Creates a [`PeView`] of a PE32+ file like [`PeView::parse`],
+    /// but skips the validation of its headers.
+    ///
+    /// This is intended for files which have already been validated.
+    /// Only checks enforcing the specification are skipped, the bounds- and
+    /// layout checks required for safely reading the file are still performed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the byte buffer is too small
+    /// to hold the structures of a PE32+ file.
+    pub fn parse_unchecked(bytes: &'a [u8]) -> Result<Self> {
+        Self::parse_with(bytes, &ParseOptions::unchecked())
+    }
+
+    /// Creates a [`PeView`] of a PE32+ image which has already been mapped into
+    /// memory, like [`PeView::parse`].
+    ///
+    /// The sections of the image are read from their RVA, as done by the loader.
+    /// Since the certificate table is not mapped by the loader,
+    /// [`PeView::certificates`] is not available.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the byte buffer does not
+    /// represent a valid and complete mapped PE32+ image.
+    pub fn parse_mapped(bytes: &'a [u8]) -> Result<Self> {
+        Self::parse_with(
+            bytes,
+            &ParseOptions {
+                mapped: true,
+                ..ParseOptions::strict()
+            },
+        )
+    }
+
+    /// Creates a [`PeView`] of a PE32+ file by parsing and validating the
+    /// specified raw byte buffer according to the specified [`ParseOptions`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the byte buffer does not
+    /// represent a valid and complete PE32+ file.
+    pub fn parse_with(bytes: &'a [u8], opts: &ParseOptions) -> Result<Self> {
+        let (dos_header, nt_header, mut iter) = Self::parse_headers(bytes, opts)?;
 
         // Allocate a vector for holding the sections
         let mut sections =
             Vec::with_capacity(nt_header.file_header.num_of_sections as _);
 
         // Iterate over each section header and save its section after validation
-        for _ in 0..nt_header.file_header.num_of_sections {
-            sections.push(Section::parse(
-                bytes,
-                data.read::<SectionHeader>()?
-                    .validate(&nt_header.optional_header)?,
-            )?)
+        for section in iter.by_ref() {
+            sections.push(section?);
         }
 
+        // Build an index of the sections sorted by their RVA for fast lookups
+        let mut sections_by_rva = (0..sections.len()).collect::<Vec<_>>();
+        sections_by_rva.sort_by_key(|&i| sections[i].header().virtual_address);
+
         Ok(Self {
             dos_header,
             nt_header,
             sections,
-            data,
+            sections_by_rva,
+            skipped: iter.skipped_checks(),
+            mapped: opts.mapped,
+            offset: 0,
+            data: ByteReader::new(bytes),
+        })
+    }
+
+    /// Creates a [`PeView`] of a PE32+ file which is embedded in the specified buffer
+    /// at the specified offset, like [`PeView::parse`].
+    ///
+    /// All file offsets of the view are relative to the start of the embedded file,
+    /// see [`PeView::offset`]. The size of the file is not known, so the view covers
+    /// the rest of the buffer, including any bytes of the container following the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the offset is outside of the buffer, or
+    /// the bytes at the offset do not represent a valid and complete PE32+ file.
+    pub fn parse_at(bytes: &'a [u8], offset: usize) -> Result<Self> {
+        Self::parse_at_with(bytes, offset, &ParseOptions::strict())
+    }
+
+    /// Creates a [`PeView`] of a PE32+ file which is embedded in the specified buffer
+    /// at the specified offset, like [`PeView::parse_at`], according to the
+    /// specified [`ParseOptions`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the offset is outside of the buffer, or
+    /// the bytes at the offset do not represent a valid and complete PE32+ file.
+    pub fn parse_at_with(
+        bytes: &'a [u8],
+        offset: usize,
+        opts: &ParseOptions,
+    ) -> Result<Self> {
+        let image = bytes
+            .get(offset..)
+            .ok_or(Error::InsufficientBufferAt(offset))?;
+
+        Ok(Self {
+            offset,
+            ..Self::parse_with(image, opts)?
         })
     }
 
+    /// Creates an iterator which lazily parses and validates the sections
+    /// of the PE32+ file represented by the specified raw byte buffer.
+    ///
+    /// Unlike [`PeView::parse`], this does not allocate.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the DOS- or NT-header
+    /// of the byte buffer are invalid.
+    pub fn sections_iter(bytes: &'a [u8]) -> Result<SectionIter<'a>> {
+        Ok(Self::parse_headers(bytes, &ParseOptions::strict())?.2)
+    }
+
+    /// Internal method for reading and validating both the DOS- and NT-header,
+    /// returning them along with an iterator over the sections following them
+    ///
+    /// The headers are copied, so they can be read from buffers of any alignment.
+    fn parse_headers(
+        bytes: &'a [u8],
+        opts: &ParseOptions,
+    ) -> Result<(DosHeader, NtHeader, SectionIter<'a>)> {
+        // Create an interface for easily reading the buffer
+        let mut data = ByteReader::new(bytes);
+        let mut skipped = Checks::NONE;
+
+        // Read and validate both the DOS- and NT-header
+        let dos_header = data.read_copied::<DosHeader>()?;
+        if opts.validate {
+            dos_header.validate()?;
+        }
+
+        // The NT header must fit into the buffer at the offset the DOS header points to
+        let nt_end =
+            (dos_header.e_lfanew as usize).checked_add(mem::size_of::<NtHeader>());
+        if nt_end.is_none_or(|end| end > bytes.len()) {
+            return Error::make_validation::<DosHeader, _>(
+                ValidationRule::NewHeaderRva(dos_header.e_lfanew),
+            );
+        }
+
+        let nt_header = data
+            .try_skip_to(Pos::Abs(dos_header.e_lfanew as _))?
+            .read_copied::<NtHeader>()?;
+        if opts.validate {
+            nt_header.validate_with(opts, &mut skipped)?;
+        }
+
+        // Jump to the RVA of the first section header
+        data.try_skip_to(Pos::Abs(section_headers_offset(&dos_header, &nt_header)))?;
+
+        Ok((
+            dos_header,
+            nt_header,
+            SectionIter::new(bytes, data, nt_header, *opts, skipped),
+        ))
+    }
+
+    /// Validates that the entry point is within a section with the
+    /// [`SectionFlags::Execute`] characteristic. Dynamic-link libraries may have no
+    /// entry point, in which case its address is zero.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::Validation`] if the entry point is within
+    /// the headers, a section which is not executable or outside of every section.
+    pub fn validate_entry_point(&self) -> Result<()> {
+        let rva = self.address_of_entry_point();
+        if rva == 0 && self.is_dll() {
+            return Ok(());
+        }
+
+        match self.section_by_addr(PeAddr::Rva(rva)) {
+            Some(s) if s.has_flag(SectionFlags::Execute) => Ok(()),
+            _ => Error::make_validation::<OptionalHeader, _>(
+                ValidationRule::EntryPoint(rva),
+            ),
+        }
+    }
+
+    /// Validates that neither the raw data nor the virtual ranges of the sections
+    /// overlap, and that the raw data of every section is within the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::Validation`] if the layout of the sections
+    /// is not coherent.
+    pub fn validate_layout(&self) -> Result<()> {
+        // Raw data ranges of the sections which have any, sorted by their file offset
+        let mut raw = self
+            .sections
+            .iter()
+            .map(|s| s.header())
+            .filter(|h| h.raw_data_size > 0)
+            .map(|h| (h.raw_data_address as u64, h.raw_data_size as u64))
+            .collect::<Vec<_>>();
+        raw.sort_unstable();
+
+        // Virtual ranges of the sections, which are already sorted by their RVA
+        let virt = self
+            .sections_by_rva
+            .iter()
+            .map(|&i| {
+                let header = self.sections[i].header();
+                let size = match header.virtual_size {
+                    0 => header.raw_data_size,
+                    v => v,
+                };
+
+                (header.virtual_address as u64, size as u64)
+            })
+            .collect::<Vec<_>>();
+
+        let file_size = self.data.bytes().len() as u64;
+        if raw.iter().any(|(addr, size)| addr + size > file_size) {
+            return Error::make_validation::<SectionHeader, _>(
+                ValidationRule::RawDataOutsideFile,
+            );
+        }
+
+        for (ranges, rule) in [
+            (raw, ValidationRule::OverlappingRawData),
+            (virt, ValidationRule::OverlappingVirtualRange),
+        ] {
+            if ranges.windows(2).any(|w| w[0].0 + w[0].1 > w[1].0) {
+                return Error::make_validation::<SectionHeader, _>(rule);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the checks which failed, but were skipped while parsing this [`PeView`].
+    pub fn skipped_checks(&self) -> Checks {
+        self.skipped
+    }
+
+    /// Returns the whole buffer this [`PeView`] was parsed from.
+    ///
+    /// File offsets, like [`PeAddr::FilePtr`], are offsets into this buffer.
+    /// For files parsed by [`PeView::parse_at`], the buffer starts at the embedded file
+    /// and ends with the buffer passed to it.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.data.bytes()
+    }
+
+    /// Returns the offset of the file within the buffer passed to [`PeView::parse_at`],
+    /// which is zero if it was parsed in any other way.
+    ///
+    /// Adding it to a file offset of this [`PeView`] yields the offset within that buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
     /// Returns a reference to the DOS-header of this [`PeView`].
     pub fn dos_header(&self) -> &DosHeader {
-        self.dos_header
+        &self.dos_header
+    }
+
+    /// Returns the DOS stub, the bytes between the end of the DOS-header and the
+    /// NT-header, which also contain the Rich header if present.
+    ///
+    /// The stub is empty if the NT-header overlaps the DOS-header.
+    pub fn dos_stub(&self) -> &'a [u8] {
+        self.data
+            .bytes()
+            .get(mem::size_of::<DosHeader>()..self.dos_header.e_lfanew as usize)
+            .unwrap_or_default()
     }
 
     /// Returns a reference to the NT-header of this [`PeView`].
     pub fn nt_header(&self) -> &NtHeader {
-        self.nt_header
+        &self.nt_header
+    }
+
+    /// Returns the `image_base` field of the optional header,
+    /// the preferred address of the image when loaded.
+    pub fn image_base(&self) -> u64 {
+        self.nt_header.optional_header.image_base
+    }
+
+    /// Returns the `address_of_entry_point` field of the optional header,
+    /// the RVA of the entry point or zero if the image has none.
+    pub fn address_of_entry_point(&self) -> u32 {
+        self.nt_header.optional_header.address_of_entry_point
+    }
+
+    /// Returns the `size_of_image` field of the optional header,
+    /// the size of the image when loaded.
+    pub fn size_of_image(&self) -> u32 {
+        self.nt_header.optional_header.size_of_image
+    }
+
+    /// Returns the `size_of_headers` field of the optional header,
+    /// the size of all headers rounded up to the file alignment.
+    pub fn size_of_headers(&self) -> u32 {
+        self.nt_header.optional_header.size_of_headers
+    }
+
+    /// Returns the `num_of_rva_and_sizes` field of the optional header,
+    /// the declared number of data directories.
+    ///
+    /// The value is not capped, unlike the directories returned by
+    /// [`NtHeader::num_of_data_directories`].
+    pub fn num_of_rva_and_sizes(&self) -> u32 {
+        self.nt_header.optional_header.num_of_rva_and_sizes
+    }
+
+    /// Returns the `loader_flags` field of the optional header, which is reserved
+    /// and zero for valid images.
+    pub fn loader_flags(&self) -> u32 {
+        self.nt_header.optional_header.loader_flags
+    }
+
+    /// Returns the file offset of the optional header of this [`PeView`].
+    pub fn optional_header_offset(&self) -> usize {
+        optional_header_offset(&self.dos_header)
+    }
+
+    /// Returns the file offset of the first section header of this [`PeView`].
+    ///
+    /// The section headers follow the optional header, whose size is declared
+    /// by the file header.
+    pub fn section_headers_offset(&self) -> usize {
+        section_headers_offset(&self.dos_header, &self.nt_header)
     }
 
     /// Returns a reference to the sections of this [`PeView`].
-    pub fn sections(&self) -> &[Section] {
+    pub fn sections(&self) -> &[Section<'_>] {
         self.sections.as_ref()
     }
 
+    /// Returns a reference to the section at the specified 0-based index
+    /// in the section table of this [`PeView`].
+    ///
+    /// Returns [`None`] if the index is out of range.
+    pub fn section(&self, index: usize) -> Option<&Section<'_>> {
+        self.sections.get(index)
+    }
+
+    /// Returns a reference to the section with the specified section number.
+    ///
+    /// Section numbers, like [`crate::symbol::Symbol::section_number`], are 1-based
+    /// indices into the section table. Zero and negative numbers have a special
+    /// meaning for symbols and do not refer to a section.
+    ///
+    /// Returns [`None`] if the number does not refer to a section.
+    pub fn section_by_number(&self, number: i16) -> Option<&Section<'_>> {
+        let index = usize::try_from(number).ok()?.checked_sub(1)?;
+        self.section(index)
+    }
+
     /// Returns a reference to a single section of this [`PeView`],
     /// who's raw data contains the specified address.
     ///
     /// Returns [`None`] if no such section is found.
-    pub fn section_by_addr(&self, addr: PeAddr) -> Option<&Section> {
-        self.sections
-            .iter()
-            .find(|s| !s.empty() && s.contains_addr(addr))
+    pub fn section_by_addr(&self, addr: PeAddr) -> Option<&Section<'_>> {
+        match addr {
+            PeAddr::Rva(rva) => {
+                // Sections of a valid image do not overlap, so only the last section
+                // starting at or before the RVA can contain it
+                let idx = self.sections_by_rva.partition_point(|&i| {
+                    self.sections[i].header().virtual_address <= rva
+                });
+
+                let found = idx
+                    .checked_sub(1)
+                    .and_then(|idx| self.sections_by_rva.get(idx))
+                    .map(|&i| &self.sections[i])
+                    .filter(|s| !s.empty() && s.contains_addr(addr));
+
+                // Images parsed without validation may have overlapping sections,
+                // in which case an earlier section can still contain the RVA
+                found.or_else(|| {
+                    self.sections
+                        .iter()
+                        .find(|s| !s.empty() && s.contains_addr(addr))
+                })
+            }
+            PeAddr::FilePtr(_) => self
+                .sections
+                .iter()
+                .find(|s| !s.empty() && s.contains_addr(addr)),
+        }
     }
 
-    /// Returns a reference to a single section of this [`PeView`],
-    /// who's name is equal to the one specified.
+    /// Returns the data of the section containing the specified address,
+    /// see [`PeView::section_by_addr`].
     ///
-    /// Returns [`None`] if no such section is found.
-    pub fn section_by_name(&self, name: &str) -> Option<&Section> {
-        self.sections.iter().find(|s| s.name() == name)
+    /// # Errors
+    ///
+    /// This function will return [`Error::SectionEmpty`]
+    /// if no section with raw data contains the address.
+    pub fn section_data_at(&self, addr: PeAddr) -> Result<&ByteReader<'_>> {
+        self.section_by_addr(addr)
+            .and_then(|s| s.data().as_ref())
+            .ok_or(Error::SectionEmpty)
     }
 
-    /// Checks if specified flag is contained in the file headers characteristics.
-    pub fn has_flag(&self, flag: FileFlags) -> bool {
-        self.nt_header.file_header.characteristics & flag as u16 == 1
+    /// Returns a reference to the section containing the specified RVA, see
+    /// [`PeView::section_by_addr`], along with the offset of the RVA within it.
+    ///
+    /// Returns [`None`] if no such section is found.
+    pub fn section_and_offset(&self, rva: u32) -> Option<(&Section<'_>, u32)> {
+        let section = self.section_by_addr(PeAddr::Rva(rva))?;
+        Some((section, rva - section.header().virtual_address))
     }
 
-    /// Returns a reference to the data directory of the specified type.
+    /// Returns a reference to the section with the conventional name of the
+    /// specified [`WellKnownSection`], see [`PeView::section_by_name`].
     ///
-    /// Returns [`None`] if the data directory is empty
-    pub fn directory(&self, typ: DataDirectoryType) -> Option<&DataDirectory> {
-        let directory =
-            &self.nt_header.optional_header.data_directories[typ as usize];
+    /// Sections may be renamed, by packers for example, so
+    /// [`PeView::section_for_directory`] should be preferred for locating data.
+    pub fn well_known(&self, section: WellKnownSection) -> Option<&Section<'_>> {
+        self.section_by_name(section.name())
+    }
 
-        if directory.size > 0 {
-            Some(directory)
-        } else {
-            None
+    /// Returns a reference to the section containing the start of the data directory
+    /// of the specified type, regardless of its name.
+    ///
+    /// Returns [`None`] if the data directory is empty or not within any section.
+    pub fn section_for_directory(
+        &self,
+        typ: DataDirectoryType,
+    ) -> Option<&Section<'_>> {
+        let directory = self.directory(typ)?;
+        match typ {
+            DataDirectoryType::CertificateTable => {
+                self.section_by_addr(PeAddr::FilePtr(directory.addr))
+            }
+            _ => self.section_by_addr(PeAddr::Rva(directory.addr)),
         }
     }
 
-    /// Returns a fallible iterator over the export table
+    /// Returns a reference to a single section of this [`PeView`],
+    /// who's name is equal to the one specified.
     ///
-    /// # Errors
+    /// Returns [`None`] if no such section is found.
+    pub fn section_by_name(&self, name: &str) -> Option<&Section<'_>> {
+        self.sections.iter().find(|s| s.name() == name)
+    }
+
+    /// Compares the data of the sections of this [`PeView`] with the ones of the
+    /// specified [`PeView`], like an image on disk with a dump of it after loading.
     ///
-    /// This function will return an error if:
-    /// - The [`DataDirectoryType::ExportTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
-    /// - The .edata section is empty or not found ([`Error::SectionEmpty`])
-    /// - The export table is malformed
-    pub fn exports(&self) -> Result<ExportTable> {
-        self.directory_table(DataDirectoryType::ExportTable)
+    /// Sections are matched by their index. A section without a counterpart of the
+    /// same name differs entirely. Only sections which differ are returned,
+    /// see [`Section::diff`].
+    pub fn diff_sections(&self, other: &PeView<'_>) -> Vec<SectionDiff> {
+        self.sections
+            .iter()
+            .enumerate()
+            .filter_map(|(index, section)| {
+                let ranges = match other
+                    .sections
+                    .get(index)
+                    .filter(|s| s.name() == section.name())
+                {
+                    Some(s) => section.diff(s),
+                    None => Vec::from([section.rva_range()]),
+                };
+
+                (!ranges.is_empty()).then_some(SectionDiff { index, ranges })
+            })
+            .collect()
     }
 
-    /// Returns a fallible iterator over the import table
+    /// Returns the number of bytes between the end of the raw data of the section at
+    /// the specified index and the raw data of the section following it in the file,
+    /// see [`Section::file_padding`].
     ///
-    /// # Errors
+    /// The last section of the file, as well as sections without raw data,
+    /// have no padding.
     ///
-    /// This function will return an error if:
-    /// - The [`DataDirectoryType::ImportTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
-    /// - The .idata section is empty or not found ([`Error::SectionEmpty`])
-    /// - The import table is malformed
-    pub fn imports(&self) -> Result<ImportTable> {
-        self.directory_table(DataDirectoryType::ImportTable)
+    /// Returns [`None`] if the index is out of range.
+    pub fn section_padding(&self, idx: usize) -> Option<u32> {
+        let section = self.sections.get(idx)?;
+        let end = section.file_range().end;
+
+        let next = self
+            .sections
+            .iter()
+            .map(Section::header)
+            .filter(|h| h.raw_data_size > 0 && h.raw_data_address >= end)
+            .map(|h| h.raw_data_address)
+            .min();
+
+        Some(match (section.empty(), next) {
+            (false, Some(next)) => section.file_padding(next),
+            _ => 0,
+        })
     }
 
-    /// Returns a fallible iterator over the base relocation table
+    /// Returns the null-terminated UTF-16LE string located at the specified RVA,
+    /// like the names of resources.
     ///
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - The [`DataDirectoryType::RelocationTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
-    /// - The .reloc section is empty or not found ([`Error::SectionEmpty`])
-    /// - The base relocation table is malformed
-    pub fn relocations(&self) -> Result<RelocationTable> {
-        self.directory_table(DataDirectoryType::RelocationTable)
+    /// - The RVA is not within the raw data of a section ([`Error::SectionEmpty`])
+    /// - The string is not terminated within the section or is not valid UTF-16 ([`Error::Malformed`])
+    pub fn wide_string_at_rva(&self, rva: u32) -> Result<String> {
+        let section = self
+            .section_by_addr(PeAddr::Rva(rva))
+            .ok_or(Error::SectionEmpty)?;
+
+        utf16_from_bytes(section.bytes_at_rva(rva)?)
     }
 
-    /// Returns a fallible iterator over the certificate table
+    /// Returns the `time_date_stamp` field of the file header,
+    /// the time the file was created at in seconds since the Unix epoch.
     ///
-    /// # Errors
+    /// Reproducible builds do not store a real time, but zero, `0xFFFFFFFF`
+    /// or a hash of the build outputs instead.
+    pub fn timestamp(&self) -> u32 {
+        self.nt_header.file_header.time_date_stamp
+    }
+
+    /// Returns the `time_date_stamp` field of the file header as a date and time.
+    ///
+    /// Returns [`None`] for the sentinel values zero and `0xFFFFFFFF`,
+    /// which do not represent a real time.
+    #[cfg(feature = "time")]
+    pub fn timestamp_datetime(&self) -> Option<time::OffsetDateTime> {
+        match self.timestamp() {
+            0 | u32::MAX => None,
+            v => time::OffsetDateTime::from_unix_timestamp(v as _).ok(),
+        }
+    }
+
+    /// Checks if this [`PeView`] is the output of a reproducible build, whose
+    /// timestamp is not a real time, see [`PeView::timestamp`].
+    ///
+    /// This is certain if the debug directory holds a REPRO entry. Otherwise the
+    /// timestamp is assumed to be a hash if it predates Windows NT 3.1, the first
+    /// release supporting PE images, or does not fit into a signed 32 bit time.
+    /// Hashes within that range can not be told apart from real times, so images
+    /// without a debug directory may be misreported as regular builds.
+    pub fn is_reproducible_build(&self) -> bool {
+        // Release date of Windows NT 3.1 (1993-07-27)
+        const FIRST_PE_TIMESTAMP: u32 = 0x2C54_7000;
+
+        let has_repro_entry = self.debug().is_ok_and(|mut entries| {
+            entries.any(|entry| {
+                entry.is_ok_and(|entry| entry.typ == DebugDirectoryEntry::TYPE_REPRO)
+            })
+        });
+
+        has_repro_entry
+            || !(FIRST_PE_TIMESTAMP..=i32::MAX as u32).contains(&self.timestamp())
+    }
+
+    /// Converts the specified RVA to a virtual address, based on the preferred image base.
+    pub const fn rva_to_va(&self, rva: u32) -> u64 {
+        self.nt_header
+            .optional_header
+            .image_base
+            .wrapping_add(rva as u64)
+    }
+
+    /// Converts the specified virtual address to an RVA, based on the preferred image base.
+    ///
+    /// Returns [`None`] if the address is below the image base
+    /// or the resulting RVA does not fit into 32 bits.
+    pub const fn va_to_rva(&self, va: u64) -> Option<u32> {
+        match va.checked_sub(self.nt_header.optional_header.image_base) {
+            Some(rva) if rva <= u32::MAX as u64 => Some(rva as u32),
+            _ => None,
+        }
+    }
+
+    /// Converts the specified RVA to a file offset.
+    ///
+    /// RVAs within the headers are mapped to themselves, while all others
+    /// are translated through the section containing them.
+    ///
+    /// Returns [`None`] if the RVA is not backed by any bytes in the file.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<u32> {
+        if rva < self.nt_header.optional_header.size_of_headers {
+            return Some(rva);
+        }
+
+        // RVAs of a mapped image are offsets into the buffer
+        if self.mapped {
+            return self.section_by_addr(PeAddr::Rva(rva)).map(|_| rva);
+        }
+
+        self.section_by_addr(PeAddr::Rva(rva))?
+            .header()
+            .rva_to_offset(rva)
+    }
+
+    /// Converts the specified address to an RVA.
+    ///
+    /// File offsets within the headers are mapped to themselves, while all others
+    /// are translated through the section containing them.
+    ///
+    /// Returns [`None`] if the file offset is not mapped into memory.
+    pub fn to_rva(&self, addr: PeAddr) -> Option<u32> {
+        match addr {
+            PeAddr::Rva(rva) => Some(rva),
+            PeAddr::FilePtr(ptr)
+                if ptr < self.nt_header.optional_header.size_of_headers =>
+            {
+                Some(ptr)
+            }
+            // Offsets into a mapped image are RVAs
+            PeAddr::FilePtr(ptr) if self.mapped => self.rva_to_offset(ptr),
+            PeAddr::FilePtr(ptr) => {
+                self.section_by_addr(addr)?.header().offset_to_rva(ptr)
+            }
+        }
+    }
+
+    /// Converts the specified address to a file offset, see [`PeView::rva_to_offset`].
+    ///
+    /// Returns [`None`] if the RVA is not backed by any bytes in the file.
+    pub fn to_file_ptr(&self, addr: PeAddr) -> Option<u32> {
+        match addr {
+            PeAddr::Rva(rva) => self.rva_to_offset(rva),
+            PeAddr::FilePtr(ptr) => Some(ptr),
+        }
+    }
+
+    /// Copies this [`PeView`] into the specified buffer in its virtual layout, like
+    /// the loader maps it into memory.
+    ///
+    /// The headers are copied to the start of the buffer and the data of each section
+    /// to its RVA, see [`Section::virtual_data`]. The rest of the first `size_of_image`
+    /// bytes is zero-filled, while any bytes past them are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The buffer is smaller than `size_of_image` ([`Error::InsufficientBuffer`])
+    /// - The virtual range of a section exceeds `size_of_image`
+    pub fn map_image(&self, out: &mut [u8]) -> Result<()> {
+        let size = self.size_of_image() as usize;
+        let out = out.get_mut(..size).ok_or(Error::InsufficientBuffer)?;
+
+        // Validate all sections first, so the buffer is not partially written
+        for section in self.sections() {
+            let start = section.header().virtual_address as usize;
+            let data = section.virtual_data().unwrap_or_default();
+            let end = start
+                .checked_add(data.len())
+                .map_or(usize::MAX, |end| end.max(section.rva_range().end as usize));
+            if end > size {
+                return Error::make_malformed::<SectionHeader, _>(format!(
+                    "exceeds the size of the image ({end:#x})"
+                ));
+            }
+        }
+
+        out.fill(0);
+
+        let headers = (self.size_of_headers() as usize)
+            .min(self.bytes().len())
+            .min(size);
+        out[..headers].copy_from_slice(&self.bytes()[..headers]);
+
+        for section in self.sections() {
+            if let Some(data) = section.virtual_data() {
+                let start = section.header().virtual_address as usize;
+                out[start..start + data.len()].copy_from_slice(data);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes the image checksum of this [`PeView`], which is compared against the
+    /// `check_sum` field of the optional header by the loader for drivers and
+    /// critical system files.
+    ///
+    /// The checksum covers all of [`PeView::bytes`], so for files parsed by
+    /// [`PeView::parse_at`] the buffer has to end with the embedded file.
+    pub fn compute_checksum(&self) -> u32 {
+        compute_checksum(self.data.bytes(), checksum_offset(&self.dos_header))
+    }
+
+    /// Returns the [`Bitness`] of this [`PeView`], derived from the optional header magic.
+    ///
+    /// Returns [`None`] if the magic is unknown, which is only accepted by
+    /// [`PeView::parse_unchecked`]. Note that the headers are always read in the
+    /// PE32+ layout, even if the magic indicates a PE32 image.
+    pub fn bitness(&self) -> Option<Bitness> {
+        self.nt_header.optional_header.bitness()
+    }
+
+    /// Checks if this [`PeView`] is a dynamic-link library.
+    pub fn is_dll(&self) -> bool {
+        self.file_characteristics().is_dll()
+    }
+
+    /// Checks if this [`PeView`] is a driver, see [`OptionalHeader::is_driver`].
+    pub fn is_driver(&self) -> bool {
+        self.nt_header.optional_header.is_driver()
+    }
+
+    /// Checks if the base relocations have been removed from this [`PeView`],
+    /// in which case it can only be loaded at its preferred image base.
+    pub fn relocations_stripped(&self) -> bool {
+        self.file_characteristics().relocs_stripped()
+    }
+
+    /// Checks if this [`PeView`] can be rebased, which requires a relocation table
+    /// and the relocations not being stripped.
+    ///
+    /// An image without a relocation table, whose relocations are not stripped
+    /// either, may simply not need any. Since this can not be told apart from a
+    /// missing table, such images are not considered relocatable.
+    pub fn can_relocate(&self) -> bool {
+        !self.relocations_stripped()
+            && self.directory(DataDirectoryType::RelocationTable).is_some()
+    }
+
+    /// Checks if specified flag is contained in the file headers characteristics.
+    pub fn has_flag(&self, flag: FileFlags) -> bool {
+        self.file_characteristics().contains(flag)
+    }
+
+    /// Returns the DLL characteristics of the optional header as a set of flags.
+    pub fn dll_characteristics(&self) -> DllCharacteristics {
+        self.nt_header.optional_header.dll_characteristics()
+    }
+
+    /// Returns the characteristics of the file header as a set of flags.
+    pub fn file_characteristics(&self) -> FileCharacteristics {
+        FileCharacteristics(self.nt_header.file_header.characteristics)
+    }
+
+    /// Returns a reference to the data directory of the specified type.
+    ///
+    /// Returns [`None`] if the data directory is empty or not declared by the
+    /// optional header, see [`NtHeader::num_of_data_directories`].
+    pub fn directory(&self, typ: DataDirectoryType) -> Option<&DataDirectory> {
+        self.nt_header.data_directory(typ).filter(|d| d.size > 0)
+    }
+
+    /// Checks if this [`PeView`] is a .NET image, which is the case if its
+    /// [`DataDirectoryType::CLRRuntimeHeader`] data directory is not empty.
+    ///
+    /// The CLR runtime header itself is not parsed.
+    pub fn is_dotnet(&self) -> bool {
+        self.directory(DataDirectoryType::CLRRuntimeHeader)
+            .is_some()
+    }
+
+    /// Returns an iterator over all non-empty data directories and their types.
+    pub fn data_directories(
+        &self,
+    ) -> impl Iterator<Item = (DataDirectoryType, &DataDirectory)> + '_ {
+        DataDirectoryType::ALL
+            .into_iter()
+            .filter_map(|typ| Some((typ, self.directory(typ)?)))
+    }
+
+    /// Returns the [`LayoutFingerprint`] of this [`PeView`], which captures its
+    /// sections and the types of its data directories.
+    pub fn layout_fingerprint(&self) -> LayoutFingerprint {
+        LayoutFingerprint {
+            sections: self
+                .sections
+                .iter()
+                .map(|s| {
+                    let header = s.header();
+                    (s.name().into(), header.characteristics, header.virtual_size)
+                })
+                .collect(),
+            directories: self.data_directories().map(|(typ, _)| typ).collect(),
+        }
+    }
+
+    /// Returns the first data directory, along with its type, which contains the
+    /// specified RVA.
+    ///
+    /// [`DataDirectoryType::CertificateTable`] is never returned, since it is addressed
+    /// by a file offset.
+    pub fn directory_containing(
+        &self,
+        rva: u32,
+    ) -> Option<(DataDirectoryType, &DataDirectory)> {
+        self.data_directories().find(|(typ, dir)| {
+            *typ != DataDirectoryType::CertificateTable && dir.contains_addr(rva)
+        })
+    }
+
+    /// Returns the RVA of the value to be stored in the global pointer register.
+    ///
+    /// The size of the [`DataDirectoryType::GlobalPointer`] data directory is always
+    /// zero, so it is never returned by [`PeView::directory`].
+    ///
+    /// Returns [`None`] if the RVA is zero.
+    pub fn global_pointer(&self) -> Option<u32> {
+        self.nt_header
+            .data_directory(DataDirectoryType::GlobalPointer)
+            .map(|d| d.addr)
+            .filter(|&addr| addr != 0)
+    }
+
+    /// Returns a fallible iterator over the export table
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::ExportTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The .edata section is empty or not found ([`Error::SectionEmpty`])
+    /// - The export table is malformed
+    pub fn exports(&self) -> Result<ExportTable<'_>> {
+        self.table()
+    }
+
+    /// Returns a fallible iterator over the import table
+    ///
+    /// The imports are read in the PE32+ layout, like the headers of this [`PeView`].
+    /// Imports of PE32 images can be read by creating an [`ImportTable`] with
+    /// [`ImportTable::with_bitness`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::ImportTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The .idata section is empty or not found ([`Error::SectionEmpty`])
+    /// - The import table is malformed
+    pub fn imports(&self) -> Result<ImportTable<'_>> {
+        self.table()
+    }
+
+    /// Returns the names of all imported modules, along with the names of the symbols
+    /// imported from each of them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while parsing the import
+    /// table, see [`PeView::imports`].
+    pub fn import_summary(&self) -> Result<Vec<(&str, Vec<ImportName<'_>>)>> {
+        self.imports()?
+            .map(|module| {
+                let module = module?;
+                let name = module.name()?;
+
+                Ok((
+                    name,
+                    module
+                        .map(|i| i.map(ImportName::from))
+                        .collect::<Result<_>>()?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns a fallible iterator over the base relocation table
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::RelocationTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The .reloc section is empty or not found ([`Error::SectionEmpty`])
+    /// - The base relocation table is malformed
+    pub fn relocations(&self) -> Result<RelocationTable<'_>> {
+        self.table()
+    }
+
+    /// Returns the number of entries in the base relocation table,
+    /// without decoding the entries themselves.
+    ///
+    /// This includes the [`RelocationKind::Absolute`] entries used to pad blocks.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while parsing the base
+    /// relocation table, see [`PeView::relocations`].
+    pub fn relocation_count(&self) -> Result<usize> {
+        self.relocations()?
+            .map(|block| block.map(|b| b.entry_count()))
+            .sum()
+    }
+
+    /// Returns a fallible iterator over the RVAs the base relocations are applied to,
+    /// each paired with the section containing it.
+    ///
+    /// The [`RelocationKind::Absolute`] entries used to pad blocks are skipped.
+    /// Relocations which are not applied to any section, which indicates a corrupted
+    /// relocation table, are paired with [`None`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the base relocation table can not be
+    /// located, see [`PeView::relocations`]. The iterator yields the errors
+    /// encountered while parsing its blocks and entries.
+    pub fn relocations_with_section(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<(Option<&Section<'_>>, u32)>>> {
+        Ok(self
+            .relocations()?
+            .flat_map(|block| {
+                let (block, err) = match block {
+                    Ok(v) => (Some(v), None),
+                    Err(e) => (None, Some(Err(e))),
+                };
+
+                block.into_iter().flatten().chain(err)
+            })
+            .filter(|reloc| {
+                !matches!(reloc, Ok(r) if r.kind == RelocationKind::Absolute)
+            })
+            .map(|reloc| {
+                reloc.map(|r| (self.section_by_addr(PeAddr::Rva(r.rva)), r.rva))
+            }))
+    }
+
+    /// Returns the resource table
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::ResourceTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The .rsrc section is empty or not found ([`Error::SectionEmpty`])
+    pub fn resources(&self) -> Result<ResourceTable<'_>> {
+        self.table()
+    }
+
+    /// Returns the version information stored in the resource table.
+    ///
+    /// The first name and language of the [`RT_VERSION`] resource type are used.
+    /// Returns [`None`] if the image has no version information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource table or the version
+    /// information is malformed.
+    pub fn version_info(&self) -> Result<Option<VersionInfo>> {
+        let resources = match self.resources() {
+            Err(Error::DataDirectoryEmpty) => return Ok(None),
+            res => res?,
+        };
+
+        let root = resources.root()?;
+        let Some(entry) = root.entry_by_id(RT_VERSION) else {
+            return Ok(None);
+        };
+        let Some(data) = root.first_data(entry)? else {
+            return Ok(None);
+        };
+
+        let bytes = self
+            .section_by_addr(PeAddr::Rva(data.data_rva))
+            .ok_or(Error::SectionEmpty)?
+            .bytes_at_rva(data.data_rva)?
+            .get(..data.size as usize)
+            .ok_or(Error::InsufficientBufferAt(data.data_rva as usize))?;
+
+        VersionInfo::parse(bytes).map(Some)
+    }
+
+    /// Returns a fallible iterator over the entries of the debug directory
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::Debug`] data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The section containing the debug directory is empty or not found ([`Error::SectionEmpty`])
+    pub fn debug(&self) -> Result<DebugTable<'_>> {
+        self.table()
+    }
+
+    /// Returns a fallible iterator over the parsed data of the entries of the
+    /// debug directory, see [`DebugEntry::parse`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the debug directory can not be read,
+    /// see [`PeView::debug`].
+    pub fn debug_entries(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<DebugEntry<'_>>> + '_> {
+        Ok(self.debug()?.map(|entry| {
+            let entry = entry?;
+            DebugEntry::parse(entry, self.debug_data(entry)?)
+        }))
+    }
+
+    /// Returns the raw data of the specified [`DebugDirectoryEntry`].
+    ///
+    /// The data is read from its file offset, or from its RVA for mapped images.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data is outside of the file,
+    /// or not within the raw data of a section for mapped images.
+    pub fn debug_data(&self, entry: &DebugDirectoryEntry) -> Result<&[u8]> {
+        let size = entry.size_of_data as usize;
+        if size == 0 {
+            return Ok(&[]);
+        }
+
+        let (bytes, pos) = if self.mapped {
+            let rva = entry.address_of_raw_data;
+            (
+                self.section_data_at(PeAddr::Rva(rva))?.bytes_at(rva as _)?,
+                rva,
+            )
+        } else {
+            let ptr = entry.pointer_to_raw_data;
+            (self.data.bytes_at(ptr as _)?, ptr)
+        };
+
+        bytes
+            .get(..size)
+            .ok_or(Error::InsufficientBufferAt(pos as usize))
+    }
+
+    /// Returns the parsed load configuration of this [`PeView`].
+    ///
+    /// The size of the structure is taken from the structure itself, since the
+    /// size of its data directory is not reliable for older linkers.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::LoadConfigTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The section containing the load configuration is empty or not found ([`Error::SectionEmpty`])
+    /// - The load configuration exceeds the raw data of its section
+    pub fn load_config(&self) -> Result<LoadConfig> {
+        let directory = self
+            .directory(DataDirectoryType::LoadConfigTable)
+            .ok_or(Error::DataDirectoryEmpty)?;
+        LoadConfig::parse(self.directory_section_bytes(directory)?)
+    }
+
+    /// Returns the parsed thread local storage directory of this [`PeView`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::TLSTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The section containing the directory is empty or not found ([`Error::SectionEmpty`])
+    /// - The directory is outside of the sections raw data
+    pub fn tls(&self) -> Result<Tls> {
+        Tls::parse(self.directory_bytes(DataDirectoryType::TLSTable)?)
+    }
+
+    /// Returns a fallible iterator over the dynamic value relocation table referenced
+    /// by the load configuration, like the fixups of an ARM64X image.
+    ///
+    /// Returns [`None`] if the load configuration references no such table.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The load configuration can not be read, see [`PeView::load_config`]
+    /// - The section of the table is not found ([`Error::SectionEmpty`])
+    /// - The table is outside of the raw data of its section or malformed
+    pub fn dynamic_relocations(&self) -> Result<Option<DynamicRelocationTable<'_>>> {
+        let Some((number, offset)) = self.load_config()?.dynamic_value_reloc_table()
+        else {
+            return Ok(None);
+        };
+
+        let section = i16::try_from(number)
+            .ok()
+            .and_then(|number| self.section_by_number(number))
+            .ok_or(Error::SectionEmpty)?;
+        let rva = section.header().virtual_address.wrapping_add(offset);
+
+        DynamicRelocationTable::parse(section.bytes_at_rva(rva)?).map(Some)
+    }
+
+    /// Returns a fallible iterator over the certificate table
+    ///
+    /// # Errors
     ///
     /// This function will return an error if:
     /// - The [`DataDirectoryType::CertificateTable`] data directory is empty ([`Error::DataDirectoryEmpty`])
     /// - The certificate table is malformed
-    pub fn certificates(&self) -> Result<CertificateTable> {
-        self.directory_table(DataDirectoryType::CertificateTable)
+    pub fn certificates(&self) -> Result<CertificateTable<'_>> {
+        self.table()
+    }
+
+    /// Checks if this [`PeView`] is signed, which is the case if its
+    /// [`DataDirectoryType::CertificateTable`] data directory is not empty.
+    ///
+    /// The certificates themselves are not verified.
+    pub fn is_signed(&self) -> bool {
+        self.directory(DataDirectoryType::CertificateTable)
+            .is_some()
+    }
+
+    /// Returns the number of entries in the certificate table,
+    /// which is zero if this [`PeView`] is not signed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error encountered while parsing the
+    /// certificate table, see [`PeView::certificates`].
+    pub fn certificate_count(&self) -> Result<usize> {
+        if !self.is_signed() {
+            return Ok(0);
+        }
+
+        self.certificates()?
+            .try_fold(0, |count, cert| cert.map(|_| count + 1))
+    }
+
+    /// Returns the raw bytes of the attribute certificate table.
+    ///
+    /// The certificate table is not loaded into memory, so the `addr` of its data
+    /// directory is a file offset rather than an RVA and must not be translated
+    /// like one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The [`DataDirectoryType::CertificateTable`] data directory is empty
+    ///   or this [`PeView`] is of a mapped image ([`Error::DataDirectoryEmpty`])
+    /// - The certificate table is outside of the buffers bounds ([`Error::InsufficientBuffer`])
+    pub fn certificate_data(&self) -> Result<&'a [u8]> {
+        let directory = self
+            .directory(DataDirectoryType::CertificateTable)
+            .filter(|_| !self.mapped)
+            .ok_or(Error::DataDirectoryEmpty)?;
+
+        let start = directory.addr as usize;
+        start
+            .checked_add(directory.size as usize)
+            .and_then(|end| self.data.bytes().get(start..end))
+            .ok_or(Error::InsufficientBufferAt(start))
+    }
+
+    /// Checks if this [`PeView`] contains a COFF symbol table, which most linked
+    /// images have stripped.
+    pub fn has_symbols(&self) -> bool {
+        let file_header = &self.nt_header.file_header;
+        file_header.ptr_to_symbol_table != 0 && file_header.num_of_symbols != 0
     }
 
-    /// Internal method for abstracting over the process of getting
-    /// parsed tables for the raw data contained in the specified data directories
-    fn directory_table<T>(&'a self, typ: DataDirectoryType) -> Result<T>
+    /// Returns the `num_of_symbols` field of the file header, which counts
+    /// auxiliary symbol records as well.
+    pub fn symbol_count(&self) -> u32 {
+        self.nt_header.file_header.num_of_symbols
+    }
+
+    /// Returns a fallible iterator over the COFF symbol table
+    ///
+    /// The iterator is empty if the file does not contain a symbol table.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::InsufficientBuffer`]
+    /// if the symbol table is outside of the buffers bounds.
+    pub fn symbols(&self) -> Result<SymbolTable<'a>> {
+        let file_header = &self.nt_header.file_header;
+        if file_header.ptr_to_symbol_table == 0 {
+            return Ok(SymbolTable::new(&[], None, 0));
+        }
+
+        Ok(SymbolTable::new(
+            self.symbol_bytes()?,
+            self.string_table(),
+            file_header.num_of_symbols,
+        ))
+    }
+
+    /// Returns the COFF string table, which immediately follows the symbol table.
+    ///
+    /// Returns [`None`] if the file has no symbol table
+    /// or the string table is outside of the buffers bounds.
+    pub fn string_table(&self) -> Option<StringTable<'a>> {
+        let file_header = &self.nt_header.file_header;
+        if file_header.ptr_to_symbol_table == 0 {
+            return None;
+        }
+
+        let offset = file_header.ptr_to_symbol_table as usize
+            + self.symbol_bytes().ok()?.len();
+        StringTable::parse(self.data.bytes_at(offset).ok()?).ok()
+    }
+
+    /// Internal method for getting the raw bytes of the symbol records
+    fn symbol_bytes(&self) -> Result<&'a [u8]> {
+        let file_header = &self.nt_header.file_header;
+        let size = (file_header.num_of_symbols as usize)
+            .checked_mul(mem::size_of::<SymbolEntry>())
+            .ok_or(Error::InsufficientBuffer)?;
+
+        self.data
+            .bytes_at(file_header.ptr_to_symbol_table as _)?
+            .get(..size)
+            .ok_or(Error::InsufficientBuffer)
+    }
+
+    /// Returns the raw bytes of the data directory of the specified type.
+    ///
+    /// The bytes are read from the section containing the directory, except for
+    /// [`DataDirectoryType::CertificateTable`], whose address is a file offset.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The directory is present, but reserved or only holding an address like
+    ///   [`DataDirectoryType::GlobalPointer`] ([`Error::UnsupportedDirectory`])
+    /// - The data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The section containing the directory is empty or not found ([`Error::SectionEmpty`])
+    /// - The directory is outside of the sections raw data ([`Error::InsufficientBufferAt`])
+    pub fn directory_bytes(&self, typ: DataDirectoryType) -> Result<&[u8]> {
+        match typ {
+            DataDirectoryType::CertificateTable => return self.certificate_data(),
+            DataDirectoryType::Architecture
+            | DataDirectoryType::GlobalPointer
+            | DataDirectoryType::Reserved => {
+                // The global pointer directory has no size, so it is present if it
+                // holds an address
+                return match self.nt_header.data_directory(typ) {
+                    Some(d) if d.addr != 0 || d.size != 0 => {
+                        Err(Error::UnsupportedDirectory(typ))
+                    }
+                    _ => Err(Error::DataDirectoryEmpty),
+                };
+            }
+            _ => {}
+        }
+
+        let directory = self.directory(typ).ok_or(Error::DataDirectoryEmpty)?;
+        self.directory_section_bytes(directory)?
+            .get(..directory.size as usize)
+            .ok_or(Error::InsufficientBufferAt(directory.addr as usize))
+    }
+
+    /// Internal method for getting the raw data of the section containing the specified
+    /// data directory, starting at the address of the directory
+    fn directory_section_bytes(&self, directory: &DataDirectory) -> Result<&[u8]> {
+        self.section_data_at(PeAddr::Rva(directory.addr))?
+            .bytes_at(directory.addr as _)
+    }
+
+    /// Returns the parsed table of the data directory selected by
+    /// [`DataDirectoryTable::typ`], like [`PeView::exports`] for [`ExportTable`].
+    ///
+    /// Tables are created without any knowledge of the image, so [`PeView::imports`]
+    /// should be preferred for reading the import table of a PE32 image.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The data directory is empty ([`Error::DataDirectoryEmpty`])
+    /// - The section containing the directory is empty or not found ([`Error::SectionEmpty`])
+    /// - The directory is outside of the sections raw data ([`Error::InsufficientBufferAt`])
+    pub fn table<T>(&'a self) -> Result<T>
     where
         T: DataDirectoryTable<'a>,
     {
-        // Get the data directory and raw data of table
+        let typ = T::typ();
         let directory = self.directory(typ).ok_or(Error::DataDirectoryEmpty)?;
-        let data = match typ {
-            DataDirectoryType::CertificateTable => &self.data,
-            _ => self
-                .section_by_addr(PeAddr::Rva(directory.addr))
-                .ok_or(Error::SectionEmpty)?
-                .data()
-                .as_ref()
-                .unwrap(),
-        };
-
-        // Validate the length of the sections raw data
-        if data.bytes().len() <= directory.size as usize {
-            return Err(Error::InsufficientBuffer);
-        }
 
-        // Get a slice of the sections raw data which contains the required table
+        // Get a slice of the raw data which contains the required table.
+        // The import table refers to data outside of its directory, like the names
+        // of the imported modules, so it is not limited to the directory
         let bytes = match typ {
-            DataDirectoryType::ExportTable
-            | DataDirectoryType::RelocationTable
-            | DataDirectoryType::CertificateTable => {
-                &data.bytes_at(directory.addr as _)?[..directory.size as _]
+            DataDirectoryType::ImportTable => {
+                self.directory_section_bytes(directory)?
             }
-            DataDirectoryType::ImportTable => data.bytes_at(directory.addr as _)?,
-            _ => unimplemented!(),
+            _ => self.directory_bytes(typ)?,
         };
 
         // Return the actual table
         Ok(T::new(bytes, directory))
     }
 }
+
+/// View of a PE32+ file which owns the buffer it was parsed from
+///
+/// Unlike [`PeView`], this is not bound to the lifetime of a buffer and can be freely
+/// returned from functions or stored alongside other data. The borrowed view is
+/// available through [`OwnedPeView::view`].
+pub struct OwnedPeView {
+    // Borrows from `bytes`, so it is dropped before the buffer is freed
+    view: ManuallyDrop<PeView<'static>>,
+    bytes: NonNull<[u8]>,
+}
+
+impl OwnedPeView {
+    /// Creates an [`OwnedPeView`] of a PE32+ file by parsing and validating the
+    /// specified buffer, like [`PeView::parse`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the buffer does not
+    /// represent a valid and complete PE32+ file.
+    pub fn parse(bytes: Vec<u8>) -> Result<Self> {
+        Self::parse_with(bytes, &ParseOptions::strict())
+    }
+
+    /// Creates an [`OwnedPeView`] of a PE32+ file by parsing and validating the
+    /// specified buffer according to the specified [`ParseOptions`],
+    /// like [`PeView::parse_with`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the buffer does not
+    /// represent a valid and complete PE32+ file.
+    pub fn parse_with(bytes: Vec<u8>, opts: &ParseOptions) -> Result<Self> {
+        // The buffer is moved to the heap and only freed when dropping the view,
+        // so references into it stay valid for as long as the view exists
+        let bytes = NonNull::from(Box::leak(bytes.into_boxed_slice()));
+
+        match PeView::parse_with(unsafe { bytes.as_ref() }, opts) {
+            Ok(view) => Ok(Self {
+                view: ManuallyDrop::new(view),
+                bytes,
+            }),
+            Err(e) => {
+                drop(unsafe { Box::from_raw(bytes.as_ptr()) });
+                Err(e)
+            }
+        }
+    }
+
+    /// Creates an [`OwnedPeView`] of a PE32+ file by reading the specified reader
+    /// to its end and parsing the result, like [`OwnedPeView::parse`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::Io`] if reading fails, or an error if the
+    /// bytes read do not represent a valid and complete PE32+ file.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Self::parse(bytes)
+    }
+
+    /// Returns a reference to the [`PeView`] of this [`OwnedPeView`].
+    pub fn view(&self) -> &PeView<'_> {
+        &self.view
+    }
+
+    /// Consumes this [`OwnedPeView`], returning the buffer it was parsed from.
+    pub fn into_bytes(self) -> Vec<u8> {
+        let mut this = ManuallyDrop::new(self);
+
+        unsafe {
+            ManuallyDrop::drop(&mut this.view);
+            Box::from_raw(this.bytes.as_ptr()).into_vec()
+        }
+    }
+}
+
+impl Drop for OwnedPeView {
+    fn drop(&mut self) {
+        unsafe {
+            ManuallyDrop::drop(&mut self.view);
+            drop(Box::from_raw(self.bytes.as_ptr()));
+        }
+    }
+}
+
+// The view only holds shared references into the buffer, which it owns exclusively
+unsafe impl Send for OwnedPeView {}
+unsafe impl Sync for OwnedPeView {}
+
+/// Writable view of a PE32+ file, which allows patching the raw data of its sections
+///
+/// The headers are parsed once on creation and can not be modified through this view.
+pub struct PeViewMut<'a> {
+    sections: Vec<SectionHeader>,
+    checksum_offset: usize,
+    mapped: bool,
+    bytes: &'a mut [u8],
+}
+
+impl<'a> PeViewMut<'a> {
+    /// Creates a [`PeViewMut`] of a PE32+ file by parsing and validating the
+    /// specified raw byte buffer representing it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the byte buffer does not
+    /// represent a valid and complete PE32+ file.
+    pub fn parse(bytes: &'a mut [u8]) -> Result<Self> {
+        Self::parse_with(bytes, &ParseOptions::strict())
+    }
+
+    /// Creates a [`PeViewMut`] of a PE32+ file by parsing and validating the
+    /// specified raw byte buffer according to the specified [`ParseOptions`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the byte buffer does not
+    /// represent a valid and complete PE32+ file.
+    pub fn parse_with(bytes: &'a mut [u8], opts: &ParseOptions) -> Result<Self> {
+        let pe = PeView::parse_with(bytes, opts)?;
+        let sections = pe.sections().iter().map(|s| *s.header()).collect();
+        let checksum_offset = checksum_offset(pe.dos_header());
+
+        Ok(Self {
+            sections,
+            checksum_offset,
+            mapped: opts.mapped,
+            bytes,
+        })
+    }
+
+    /// Returns a read-only [`PeView`] of the current state of the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the patched file can no longer be parsed.
+    pub fn view(&self) -> Result<PeView<'_>> {
+        PeView::parse_with(
+            self.bytes,
+            &ParseOptions {
+                mapped: self.mapped,
+                ..ParseOptions::unchecked()
+            },
+        )
+    }
+
+    /// Returns a reference to the raw bytes of the file.
+    pub fn bytes(&self) -> &[u8] {
+        self.bytes
+    }
+
+    /// Returns a single section of this [`PeViewMut`], who's name is equal to the one
+    /// specified.
+    ///
+    /// Returns [`None`] if no such section is found or the section has no data.
+    pub fn section_by_name_mut(&mut self, name: &str) -> Option<SectionMut<'_>> {
+        let header = self
+            .sections
+            .iter()
+            .find(|h| str_from_padded_bytes(&h.name).is_ok_and(|n| n == name))?;
+
+        let range = self.data_range(header)?;
+        Some(SectionMut::new(header, &mut self.bytes[range]))
+    }
+
+    /// Copies the specified data into the file, starting at the specified RVA.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - No section contains the RVA ([`Error::SectionEmpty`])
+    /// - The RVA is within the zero-filled part of the section ([`Error::UninitializedData`])
+    /// - The data exceeds the raw data of the section ([`Error::InsufficientBufferAt`])
+    ///
+    /// Sections of a mapped image have no zero-filled part, their data spans the
+    /// virtual size like [`Section::parse_mapped`].
+    pub fn write_at_rva(&mut self, rva: u32, data: &[u8]) -> Result<()> {
+        let (header, range) = self
+            .sections
+            .iter()
+            .filter(|h| h.contains_rva(rva))
+            .find_map(|h| Some((h, self.data_range(h)?)))
+            .ok_or(Error::SectionEmpty)?;
+        let offset = match self.mapped {
+            true => rva as usize,
+            false => {
+                header.rva_to_offset(rva).ok_or(Error::UninitializedData)? as usize
+            }
+        };
+
+        // The write may not leave the data of the section
+        self.bytes
+            .get_mut(offset..range.end)
+            .and_then(|bytes| bytes.get_mut(..data.len()))
+            .ok_or(Error::InsufficientBufferAt(rva as usize))?
+            .copy_from_slice(data);
+
+        Ok(())
+    }
+
+    /// Internal method for getting the range of the buffer holding the data of the
+    /// section represented by the specified header, like [`Section::parse`] or
+    /// [`Section::parse_mapped`] locate it.
+    ///
+    /// Returns [`None`] if the section has no data. The bounds were checked while
+    /// parsing.
+    fn data_range(&self, header: &SectionHeader) -> Option<Range<usize>> {
+        let (start, size) = match (self.mapped, header.virtual_size) {
+            (true, 0) => (header.virtual_address, header.raw_data_size),
+            (true, v) => (header.virtual_address, v),
+            (false, _) => (header.raw_data_address, header.raw_data_size),
+        };
+
+        (size > 0).then_some(start as usize..start as usize + size as usize)
+    }
+
+    /// Recomputes the image checksum and writes it to the `check_sum` field of the
+    /// optional header, which is required after patching drivers.
+    pub fn fix_checksum(&mut self) {
+        let checksum = compute_checksum(self.bytes, self.checksum_offset);
+
+        // The field was read while parsing, so it is within the buffers bounds
+        self.bytes[self.checksum_offset..][..mem::size_of::<u32>()]
+            .copy_from_slice(&checksum.to_le_bytes());
+    }
+}
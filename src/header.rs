@@ -1,6 +1,15 @@
-use crate::{dir::DataDirectory, error::*, impl_from_bytes, mem::FromBytes};
-use alloc::{format, string::ToString};
-use core::{mem, str};
+use crate::{
+    dir::{DataDirectory, DataDirectoryType},
+    error::*,
+    file::{Check, Checks, ParseOptions},
+    impl_from_bytes,
+    mem::{align_up, FromBytes},
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{mem, slice, str};
 
 /// Native structure
 #[derive(Clone, Copy)]
@@ -32,16 +41,14 @@ impl DosHeader {
 
     pub fn validate(&self) -> Result<&Self> {
         if self.e_magic != Self::DOS_SIGNATURE {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid magic number ({:#04x})",
-                self.e_magic
+            return Error::make_validation::<Self, _>(ValidationRule::Signature(
+                self.e_magic as u32,
             ));
         }
 
-        if self.e_lfanew as usize % mem::size_of::<u32>() != 0 {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid new header rva ({:#08x})",
-                self.e_lfanew
+        if !(self.e_lfanew as usize).is_multiple_of(mem::size_of::<u32>()) {
+            return Error::make_validation::<Self, _>(ValidationRule::NewHeaderRva(
+                self.e_lfanew,
             ));
         }
 
@@ -62,12 +69,29 @@ pub struct FileHeader {
     pub characteristics: u16,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FileMachine {
     I386 = 0x014c,
+    ARMNT = 0x01c4,
     IA64 = 0x0200,
     AMD64 = 0x8664,
+    ARM64EC = 0xa641,
+    ARM64 = 0xaa64,
 }
 
+impl FileMachine {
+    /// Every known [`FileMachine`], ordered by its value
+    pub const ALL: [Self; 6] = [
+        Self::I386,
+        Self::ARMNT,
+        Self::IA64,
+        Self::AMD64,
+        Self::ARM64EC,
+        Self::ARM64,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FileFlags {
     RelocsStripped = 0x1,
     ExecutableImage = 0x2,
@@ -81,28 +105,223 @@ pub enum FileFlags {
     UpSystemOnly = 0x4000,
 }
 
+impl FileFlags {
+    /// Every [`FileFlags`], ordered by its value
+    pub const ALL: [Self; 10] = [
+        Self::RelocsStripped,
+        Self::ExecutableImage,
+        Self::LargeAddress,
+        Self::Machine32,
+        Self::DebugStripped,
+        Self::RemovableRun,
+        Self::NetworkRun,
+        Self::SystemFile,
+        Self::Dll,
+        Self::UpSystemOnly,
+    ];
+
+    /// Returns the name of this [`FileFlags`], as used by `winnt.h` without the
+    /// `IMAGE_FILE_` prefix.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::RelocsStripped => "RELOCS_STRIPPED",
+            Self::ExecutableImage => "EXECUTABLE_IMAGE",
+            Self::LargeAddress => "LARGE_ADDRESS_AWARE",
+            Self::Machine32 => "32BIT_MACHINE",
+            Self::DebugStripped => "DEBUG_STRIPPED",
+            Self::RemovableRun => "REMOVABLE_RUN_FROM_SWAP",
+            Self::NetworkRun => "NET_RUN_FROM_SWAP",
+            Self::SystemFile => "SYSTEM",
+            Self::Dll => "DLL",
+            Self::UpSystemOnly => "UP_SYSTEM_ONLY",
+        }
+    }
+}
+
+/// Set of [`FileFlags`] contained in the characteristics of a [`FileHeader`]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FileCharacteristics(pub u16);
+
+impl FileCharacteristics {
+    /// Checks if the specified flag is contained in this set.
+    pub fn contains(&self, flag: FileFlags) -> bool {
+        self.0 & flag as u16 != 0
+    }
+
+    /// Returns the names of the flags contained in this set, separated by `|`,
+    /// like `EXECUTABLE_IMAGE|LARGE_ADDRESS_AWARE`.
+    ///
+    /// Bits which do not belong to any [`FileFlags`] are not included.
+    pub fn flags_string(&self) -> String {
+        join_flag_names(
+            FileFlags::ALL
+                .iter()
+                .filter(|&&f| self.contains(f))
+                .map(FileFlags::name),
+        )
+    }
+
+    /// Checks if the file is a dynamic-link library.
+    pub fn is_dll(&self) -> bool {
+        self.contains(FileFlags::Dll)
+    }
+
+    /// Checks if the file is a valid image which can be run.
+    pub fn is_executable_image(&self) -> bool {
+        self.contains(FileFlags::ExecutableImage)
+    }
+
+    /// Checks if the file is a system file.
+    pub fn is_system(&self) -> bool {
+        self.contains(FileFlags::SystemFile)
+    }
+
+    /// Checks if the base relocations have been removed from the file.
+    pub fn relocs_stripped(&self) -> bool {
+        self.contains(FileFlags::RelocsStripped)
+    }
+
+    /// Checks if the debugging information has been removed from the file.
+    pub fn debug_stripped(&self) -> bool {
+        self.contains(FileFlags::DebugStripped)
+    }
+
+    /// Checks if the file can handle addresses larger than 2 GB.
+    pub fn large_address_aware(&self) -> bool {
+        self.contains(FileFlags::LargeAddress)
+    }
+}
+
+/// Flag contained in the DLL characteristics of an [`OptionalHeader`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DllFlags {
+    HighEntropyVa = 0x20,
+    DynamicBase = 0x40,
+    ForceIntegrity = 0x80,
+    NxCompat = 0x100,
+    NoIsolation = 0x200,
+    NoSeh = 0x400,
+    NoBind = 0x800,
+    AppContainer = 0x1000,
+    WdmDriver = 0x2000,
+    GuardCf = 0x4000,
+    TerminalServerAware = 0x8000,
+}
+
+impl DllFlags {
+    /// Every [`DllFlags`], ordered by its value
+    pub const ALL: [Self; 11] = [
+        Self::HighEntropyVa,
+        Self::DynamicBase,
+        Self::ForceIntegrity,
+        Self::NxCompat,
+        Self::NoIsolation,
+        Self::NoSeh,
+        Self::NoBind,
+        Self::AppContainer,
+        Self::WdmDriver,
+        Self::GuardCf,
+        Self::TerminalServerAware,
+    ];
+
+    /// Returns the name of this [`DllFlags`], as used by `winnt.h` without the
+    /// `IMAGE_DLLCHARACTERISTICS_` prefix.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::HighEntropyVa => "HIGH_ENTROPY_VA",
+            Self::DynamicBase => "DYNAMIC_BASE",
+            Self::ForceIntegrity => "FORCE_INTEGRITY",
+            Self::NxCompat => "NX_COMPAT",
+            Self::NoIsolation => "NO_ISOLATION",
+            Self::NoSeh => "NO_SEH",
+            Self::NoBind => "NO_BIND",
+            Self::AppContainer => "APPCONTAINER",
+            Self::WdmDriver => "WDM_DRIVER",
+            Self::GuardCf => "GUARD_CF",
+            Self::TerminalServerAware => "TERMINAL_SERVER_AWARE",
+        }
+    }
+}
+
+/// Set of [`DllFlags`] contained in the DLL characteristics of an [`OptionalHeader`]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DllCharacteristics(pub u16);
+
+impl DllCharacteristics {
+    /// Checks if the specified flag is contained in this set.
+    pub fn contains(&self, flag: DllFlags) -> bool {
+        self.0 & flag as u16 != 0
+    }
+
+    /// Returns the names of the flags contained in this set, separated by `|`,
+    /// like `DYNAMIC_BASE|NX_COMPAT`.
+    ///
+    /// Bits which do not belong to any [`DllFlags`] are not included.
+    pub fn flags_string(&self) -> String {
+        join_flag_names(
+            DllFlags::ALL
+                .iter()
+                .filter(|&&f| self.contains(f))
+                .map(DllFlags::name),
+        )
+    }
+}
+
+/// Joins the specified names of flags, separated by `|`
+fn join_flag_names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    let mut joined = String::new();
+    for name in names {
+        if !joined.is_empty() {
+            joined.push('|');
+        }
+        joined.push_str(name);
+    }
+
+    joined
+}
+
 impl FileHeader {
-    const MIN_NUM_OF_SECTIONS: u16 = 2;
+    /// An image without sections has nothing to map
+    const MIN_NUM_OF_SECTIONS: u16 = 1;
+    /// Limit imposed by the loader rather than the file format,
+    /// which is only enforced by [`ParseOptions::strict`]
     const MAX_NUM_OF_SECTIONS: u16 = 96;
 
     pub fn validate(&self) -> Result<&Self> {
-        if self.machine != FileMachine::AMD64 as u16
-            && self.machine != FileMachine::I386 as u16
-            && self.machine != FileMachine::IA64 as u16
+        self.validate_with(&ParseOptions::strict(), &mut Checks::default())
+    }
+
+    /// Validates this [`FileHeader`], ignoring the checks skipped by the specified
+    /// [`ParseOptions`] and recording them in `skipped`.
+    pub fn validate_with(
+        &self,
+        opts: &ParseOptions,
+        skipped: &mut Checks,
+    ) -> Result<&Self> {
+        if !opts
+            .allowed_machines
+            .iter()
+            .any(|&m| m as u16 == self.machine)
         {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid machine architecture ({:#04x})",
-                self.machine
+            return Error::make_validation::<Self, _>(ValidationRule::Machine(
+                self.machine,
             ));
         }
 
-        if self.num_of_sections < Self::MIN_NUM_OF_SECTIONS
-            || self.num_of_sections > Self::MAX_NUM_OF_SECTIONS
-        {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid number of sections ({})",
-                self.num_of_sections
-            ));
+        if self.num_of_sections < Self::MIN_NUM_OF_SECTIONS {
+            return Error::make_validation::<Self, _>(
+                ValidationRule::NumOfSections(self.num_of_sections),
+            );
+        }
+
+        if self.num_of_sections > Self::MAX_NUM_OF_SECTIONS {
+            opts.fail(
+                Check::SectionCount,
+                skipped,
+                Error::make_validation::<Self, _>(ValidationRule::NumOfSections(
+                    self.num_of_sections,
+                )),
+            )?;
         }
 
         if self.size_of_optional_header == 0 {
@@ -110,8 +329,8 @@ impl FileHeader {
         }
 
         if self.characteristics == 0 {
-            return Error::make_malformed::<Self, _>(
-                "has missing characteristics".to_string(),
+            return Error::make_validation::<Self, _>(
+                ValidationRule::MissingCharacteristics,
             );
         }
 
@@ -119,6 +338,38 @@ impl FileHeader {
     }
 }
 
+/// Subsystem required to run an image
+/// Values are defined by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#windows-subsystem)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Subsystem {
+    Unknown = 0,
+    Native = 1,
+    WindowsGui = 2,
+    WindowsCui = 3,
+    Os2Cui = 5,
+    PosixCui = 7,
+    NativeWindows = 8,
+    WindowsCeGui = 9,
+    EfiApplication = 10,
+    EfiBootServiceDriver = 11,
+    EfiRuntimeDriver = 12,
+    EfiRom = 13,
+    Xbox = 14,
+    WindowsBootApplication = 16,
+}
+
+/// Width of addresses and pointer sized fields of an image
+///
+/// Only PE32+ headers are parsed, PE32 images are supported through the raw tables
+/// like [`crate::dir::ImportTable::with_bitness`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bitness {
+    /// PE32 image
+    Bits32,
+    /// PE32+ image
+    Bits64,
+}
+
 /// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#optional-header-image-only)
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -157,63 +408,110 @@ pub struct OptionalHeader {
 
 impl OptionalHeader {
     const NT_PAGE_SIZE: u32 = 0x1000;
+    const NT_OPTIONAL_HDR32_MAGIC: u16 = 0x10B;
     const NT_OPTIONAL_HDR64_MAGIC: u16 = 0x20B;
 
+    /// Returns the DLL characteristics of this [`OptionalHeader`] as a set of flags.
+    pub fn dll_characteristics(&self) -> DllCharacteristics {
+        DllCharacteristics(self.dll_characteristics)
+    }
+
+    /// Returns the [`Bitness`] indicated by the `magic` field of this [`OptionalHeader`].
+    ///
+    /// Returns [`None`] if the magic is unknown.
+    pub fn bitness(&self) -> Option<Bitness> {
+        match self.magic {
+            Self::NT_OPTIONAL_HDR32_MAGIC => Some(Bitness::Bits32),
+            Self::NT_OPTIONAL_HDR64_MAGIC => Some(Bitness::Bits64),
+            _ => None,
+        }
+    }
+
+    /// Checks if the image runs in kernel mode, either because it targets the native
+    /// subsystem or is marked as a WDM driver.
+    ///
+    /// Native user mode programs, like the session manager, are reported as well.
+    pub fn is_driver(&self) -> bool {
+        self.subsystem == Subsystem::Native as u16
+            || self.dll_characteristics().contains(DllFlags::WdmDriver)
+    }
+
     pub fn validate(&self) -> Result<&Self> {
+        self.validate_with(&ParseOptions::strict(), &mut Checks::default())
+    }
+
+    /// Validates this [`OptionalHeader`], ignoring the checks skipped by the specified
+    /// [`ParseOptions`] and recording them in `skipped`.
+    pub fn validate_with(
+        &self,
+        opts: &ParseOptions,
+        skipped: &mut Checks,
+    ) -> Result<&Self> {
         if self.magic != Self::NT_OPTIONAL_HDR64_MAGIC {
             return Err(Error::InvalidFileFormat);
         }
 
-        if self.image_base % 0x10000 != 0 {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid image base ({:#016x})",
-                self.image_base
-            ));
+        if !self.image_base.is_multiple_of(0x10000) {
+            opts.fail(
+                Check::ImageBase,
+                skipped,
+                Error::make_validation::<Self, _>(ValidationRule::ImageBase(
+                    self.image_base,
+                )),
+            )?;
         }
 
         if self.section_alignment < self.file_alignment {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid section alignment ({:#08x})",
-                self.section_alignment
-            ));
+            return Error::make_validation::<Self, _>(
+                ValidationRule::SectionAlignment(self.section_alignment),
+            );
         }
 
-        if self.file_alignment % 2 != 0
+        if !self.file_alignment.is_multiple_of(2)
             || self.file_alignment < 512
             || self.file_alignment > 0x10000
             || (self.section_alignment < Self::NT_PAGE_SIZE
                 && self.file_alignment != self.section_alignment)
         {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid file alignment ({:#08x})",
-                self.file_alignment
-            ));
+            opts.fail(
+                Check::FileAlignment,
+                skipped,
+                Error::make_validation::<Self, _>(ValidationRule::FileAlignment(
+                    self.file_alignment,
+                )),
+            )?;
         }
 
         if self.win32_version_value != 0 {
-            return Error::make_malformed::<Self, _>(
-                "has non zero reserved field 'win32_version_value'".to_string(),
-            );
+            opts.fail(
+                Check::ReservedField,
+                skipped,
+                Error::make_validation::<Self, _>(ValidationRule::ReservedField(
+                    "win32_version_value",
+                )),
+            )?;
         }
 
-        if self.size_of_image % self.section_alignment != 0 {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid size of image ({:#08x})",
-                self.size_of_image
+        if !self.size_of_image.is_multiple_of(self.section_alignment) {
+            return Error::make_validation::<Self, _>(ValidationRule::SizeOfImage(
+                self.size_of_image,
             ));
         }
 
-        if self.size_of_headers % self.file_alignment != 0 {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid size of headers ({:#08x})",
-                self.size_of_headers
-            ));
+        if !self.size_of_headers.is_multiple_of(self.file_alignment) {
+            return Error::make_validation::<Self, _>(
+                ValidationRule::SizeOfHeaders(self.size_of_headers),
+            );
         }
 
         if self.loader_flags != 0 {
-            return Error::make_malformed::<Self, _>(
-                "has non zero reserved field 'loader_flags'".to_string(),
-            );
+            opts.fail(
+                Check::ReservedField,
+                skipped,
+                Error::make_validation::<Self, _>(ValidationRule::ReservedField(
+                    "loader_flags",
+                )),
+            )?;
         }
 
         Ok(self)
@@ -233,18 +531,51 @@ impl NtHeader {
     const NT_SIGNATURE: u32 = 0x00004550;
 
     pub fn validate(&self) -> Result<&Self> {
+        self.validate_with(&ParseOptions::strict(), &mut Checks::default())
+    }
+
+    /// Validates this [`NtHeader`], ignoring the checks skipped by the specified
+    /// [`ParseOptions`] and recording them in `skipped`.
+    pub fn validate_with(
+        &self,
+        opts: &ParseOptions,
+        skipped: &mut Checks,
+    ) -> Result<&Self> {
         if self.signature != Self::NT_SIGNATURE {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid signature ({:#08x})",
-                self.signature
+            return Error::make_validation::<Self, _>(ValidationRule::Signature(
+                self.signature,
             ));
         }
 
-        self.file_header.validate()?;
-        self.optional_header.validate()?;
+        self.file_header.validate_with(opts, skipped)?;
+        self.optional_header.validate_with(opts, skipped)?;
 
         Ok(self)
     }
+
+    /// Returns the number of data directories declared by the `num_of_rva_and_sizes`
+    /// field of the [`OptionalHeader`].
+    ///
+    /// The count is limited to the directories which fit into the declared size of the
+    /// optional header, as well as the 16 directories held by [`OptionalHeader`].
+    pub fn num_of_data_directories(&self) -> usize {
+        let fitting = (self.file_header.size_of_optional_header as usize)
+            .saturating_sub(mem::offset_of!(OptionalHeader, data_directories))
+            / mem::size_of::<DataDirectory>();
+
+        (self.optional_header.num_of_rva_and_sizes as usize)
+            .min(fitting)
+            .min(self.optional_header.data_directories.len())
+    }
+
+    /// Returns a reference to the data directory of the specified type.
+    ///
+    /// Returns [`None`] if the directory is not declared by the optional header,
+    /// see [`NtHeader::num_of_data_directories`].
+    pub fn data_directory(&self, typ: DataDirectoryType) -> Option<&DataDirectory> {
+        self.optional_header.data_directories[..self.num_of_data_directories()]
+            .get(typ as usize)
+    }
 }
 
 /// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#section-table-section-headers)
@@ -263,6 +594,7 @@ pub struct SectionHeader {
     pub characteristics: u32,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum SectionFlags {
     CntCode = 0x20,
@@ -279,30 +611,194 @@ pub enum SectionFlags {
     Write = 0x80000000,
 }
 
+impl SectionFlags {
+    /// Every [`SectionFlags`], ordered by its value
+    pub const ALL: [Self; 12] = [
+        Self::CntCode,
+        Self::CntInitData,
+        Self::CntUninitData,
+        Self::Gprel,
+        Self::NrelocOvfl,
+        Self::Discardable,
+        Self::NotCached,
+        Self::NotPaged,
+        Self::Shared,
+        Self::Execute,
+        Self::Read,
+        Self::Write,
+    ];
+
+    /// Returns the short name of this [`SectionFlags`], as used by `winnt.h` without
+    /// the `IMAGE_SCN_` prefix and the `CNT_`, `LNK_` or `MEM_` qualifier.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::CntCode => "CODE",
+            Self::CntInitData => "INITIALIZED_DATA",
+            Self::CntUninitData => "UNINITIALIZED_DATA",
+            Self::Gprel => "GPREL",
+            Self::NrelocOvfl => "NRELOC_OVFL",
+            Self::Discardable => "DISCARDABLE",
+            Self::NotCached => "NOT_CACHED",
+            Self::NotPaged => "NOT_PAGED",
+            Self::Shared => "SHARED",
+            Self::Execute => "EXECUTE",
+            Self::Read => "READ",
+            Self::Write => "WRITE",
+        }
+    }
+}
+
+/// Set of [`SectionFlags`] contained in the characteristics of a [`SectionHeader`]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SectionCharacteristics(pub u32);
+
+impl SectionCharacteristics {
+    /// Checks if the specified flag is contained in this set.
+    pub fn contains(&self, flag: SectionFlags) -> bool {
+        self.0 & flag as u32 != 0
+    }
+
+    /// Returns the names of the flags contained in this set, separated by `|`,
+    /// like `CODE|EXECUTE|READ`.
+    ///
+    /// The alignment and any other bits which do not belong to a [`SectionFlags`]
+    /// are not included.
+    pub fn flags_string(&self) -> String {
+        join_flag_names(
+            SectionFlags::ALL
+                .iter()
+                .filter(|&&f| self.contains(f))
+                .map(SectionFlags::name),
+        )
+    }
+
+    /// Checks if the section can be executed as code.
+    pub fn is_executable(&self) -> bool {
+        self.contains(SectionFlags::Execute)
+    }
+
+    /// Checks if the section can be read.
+    pub fn is_readable(&self) -> bool {
+        self.contains(SectionFlags::Read)
+    }
+
+    /// Checks if the section can be written to.
+    pub fn is_writable(&self) -> bool {
+        self.contains(SectionFlags::Write)
+    }
+
+    /// Checks if the section contains executable code.
+    pub fn is_code(&self) -> bool {
+        self.contains(SectionFlags::CntCode)
+    }
+
+    /// Checks if the section contains initialized data.
+    pub fn is_initialized_data(&self) -> bool {
+        self.contains(SectionFlags::CntInitData)
+    }
+
+    /// Checks if the section contains uninitialized data.
+    pub fn is_uninitialized_data(&self) -> bool {
+        self.contains(SectionFlags::CntUninitData)
+    }
+
+    /// Checks if the section can be discarded as needed.
+    pub fn is_discardable(&self) -> bool {
+        self.contains(SectionFlags::Discardable)
+    }
+
+    /// Checks if the section can be shared in memory.
+    pub fn is_shared(&self) -> bool {
+        self.contains(SectionFlags::Shared)
+    }
+}
+
 impl SectionHeader {
+    /// Converts the specified RVA to a file offset within the raw data of this section.
+    ///
+    /// Returns [`None`] if the RVA is outside of the section
+    /// or within its zero-filled part, which has no backing bytes in the file.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<u32> {
+        if !self.contains_rva(rva)
+            || rva - self.virtual_address >= self.raw_data_size
+        {
+            return None;
+        }
+
+        self.raw_data_address
+            .checked_add(rva - self.virtual_address)
+    }
+
+    /// Converts the specified file offset within the raw data of this section to an RVA.
+    ///
+    /// Returns [`None`] if the offset is outside of the raw data of the section
+    /// or within the padding following its virtual size, which is not mapped.
+    pub fn offset_to_rva(&self, offset: u32) -> Option<u32> {
+        let delta = offset.checked_sub(self.raw_data_address)?;
+        if delta >= self.raw_data_size || delta >= self.virtual_size {
+            return None;
+        }
+
+        self.virtual_address.checked_add(delta)
+    }
+
+    /// Checks if the specified RVA is within the virtual range of this section.
+    pub fn contains_rva(&self, rva: u32) -> bool {
+        self.virtual_address
+            .checked_add(self.virtual_size)
+            .is_some_and(|end| (self.virtual_address..end).contains(&rva))
+    }
+
     pub fn validate(&self, optional_header: &OptionalHeader) -> Result<&Self> {
+        self.validate_with(
+            optional_header,
+            &ParseOptions::strict(),
+            &mut Checks::default(),
+        )
+    }
+
+    /// Validates this [`SectionHeader`], ignoring the checks skipped by the specified
+    /// [`ParseOptions`] and recording them in `skipped`.
+    pub fn validate_with(
+        &self,
+        optional_header: &OptionalHeader,
+        opts: &ParseOptions,
+        skipped: &mut Checks,
+    ) -> Result<&Self> {
         str::from_utf8(self.name.as_slice())
             .map_err(|e| Error::Malformed(e.to_string()))?;
 
-        if self.raw_data_size % optional_header.file_alignment != 0 {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid size of raw data ({:#08x})",
-                self.raw_data_size
-            ));
+        if !self
+            .raw_data_size
+            .is_multiple_of(optional_header.file_alignment)
+        {
+            opts.fail(
+                Check::FileAlignment,
+                skipped,
+                Error::make_validation::<Self, _>(ValidationRule::RawDataSize(
+                    self.raw_data_size,
+                )),
+            )?;
         }
 
-        if self.raw_data_address % optional_header.file_alignment != 0 {
-            return Error::make_malformed::<Self, _>(format!(
-                "has invalid address of raw data ({:#08x})",
-                self.raw_data_address
-            ));
+        if !self
+            .raw_data_address
+            .is_multiple_of(optional_header.file_alignment)
+        {
+            opts.fail(
+                Check::FileAlignment,
+                skipped,
+                Error::make_validation::<Self, _>(ValidationRule::RawDataAddress(
+                    self.raw_data_address,
+                )),
+            )?;
         }
 
         if (self.virtual_size == 0 && self.raw_data_size == 0)
             || (self.virtual_address == 0 && self.raw_data_address == 0)
         {
-            return Error::make_malformed::<Self, _>(
-                "has invalid section size or address".to_string(),
+            return Error::make_validation::<Self, _>(
+                ValidationRule::SectionSizeOrAddress,
             );
         }
 
@@ -317,3 +813,183 @@ impl_from_bytes!(
     NtHeader,
     SectionHeader
 );
+
+/// Builder for a minimal, valid set of headers and sections of a PE32+ image.
+///
+/// The defaults describe an AMD64 console executable. Fields derived from the added
+/// sections, like the number of sections, the size of the headers and the size of the
+/// image, as well as the addresses of the sections are computed when building and
+/// overwrite any previously set value.
+#[derive(Clone)]
+pub struct NtHeaderBuilder {
+    nt_header: NtHeader,
+    sections: Vec<(SectionHeader, Vec<u8>)>,
+}
+
+impl NtHeaderBuilder {
+    /// Creates a new [`NtHeaderBuilder`] without any sections.
+    ///
+    /// Note that at least one section is required for the headers to be valid.
+    pub fn new() -> Self {
+        let mut nt_header = zeroed::<NtHeader>();
+        nt_header.signature = NtHeader::NT_SIGNATURE;
+
+        let file_header = &mut nt_header.file_header;
+        file_header.machine = FileMachine::AMD64 as u16;
+        file_header.size_of_optional_header =
+            mem::size_of::<OptionalHeader>() as u16;
+        file_header.characteristics =
+            FileFlags::ExecutableImage as u16 | FileFlags::LargeAddress as u16;
+
+        let optional_header = &mut nt_header.optional_header;
+        optional_header.magic = OptionalHeader::NT_OPTIONAL_HDR64_MAGIC;
+        optional_header.image_base = 0x140000000;
+        optional_header.section_alignment = OptionalHeader::NT_PAGE_SIZE;
+        optional_header.file_alignment = 0x200;
+        optional_header.major_os_version = 6;
+        optional_header.major_subsystem_version = 6;
+        optional_header.subsystem = Subsystem::WindowsCui as u16;
+        optional_header.size_of_stack_reserve = 0x100000;
+        optional_header.size_of_stack_commit = 0x1000;
+        optional_header.size_of_heap_reserve = 0x100000;
+        optional_header.size_of_heap_commit = 0x1000;
+        optional_header.num_of_rva_and_sizes =
+            optional_header.data_directories.len() as u32;
+
+        Self {
+            nt_header,
+            sections: Vec::new(),
+        }
+    }
+
+    /// Overrides fields of the [`FileHeader`].
+    pub fn file_header(mut self, f: impl FnOnce(&mut FileHeader)) -> Self {
+        f(&mut self.nt_header.file_header);
+        self
+    }
+
+    /// Overrides fields of the [`OptionalHeader`].
+    pub fn optional_header(mut self, f: impl FnOnce(&mut OptionalHeader)) -> Self {
+        f(&mut self.nt_header.optional_header);
+        self
+    }
+
+    /// Sets the data directory of the specified [`DataDirectoryType`].
+    pub fn data_directory(self, typ: DataDirectoryType, dir: DataDirectory) -> Self {
+        self.optional_header(|h| h.data_directories[typ as usize] = dir)
+    }
+
+    /// Appends a section, which is placed after the previously added sections.
+    ///
+    /// Its virtual size is the larger of `virtual_size` and the length of `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is longer than 8 bytes.
+    pub fn section(
+        mut self,
+        name: &str,
+        characteristics: u32,
+        virtual_size: u32,
+        data: &[u8],
+    ) -> Self {
+        let mut header = zeroed::<SectionHeader>();
+        header.name[..name.len()].copy_from_slice(name.as_bytes());
+        header.virtual_size = virtual_size.max(data.len() as u32);
+        header.characteristics = characteristics;
+
+        self.sections.push((header, data.to_vec()));
+        self
+    }
+
+    /// Returns the [`NtHeader`] and the [`SectionHeader`]s with all derived fields computed.
+    pub fn build(&self) -> (NtHeader, Vec<SectionHeader>) {
+        let mut nt_header = self.nt_header;
+        let optional_header = &mut nt_header.optional_header;
+        let (section_alignment, file_alignment) = (
+            optional_header.section_alignment as usize,
+            optional_header.file_alignment as usize,
+        );
+
+        let headers_size = mem::size_of::<DosHeader>()
+            + mem::size_of::<NtHeader>()
+            + mem::size_of::<SectionHeader>() * self.sections.len();
+        let headers_size = align_up(headers_size, file_alignment);
+
+        // Lay out the sections consecutively, both in memory and in the file
+        let mut rva = align_up(headers_size, section_alignment);
+        let mut offset = headers_size;
+        let mut headers = Vec::with_capacity(self.sections.len());
+        for (header, data) in &self.sections {
+            let mut header = *header;
+            let raw_size = align_up(data.len(), file_alignment);
+
+            header.virtual_address = rva as u32;
+            header.raw_data_size = raw_size as u32;
+            header.raw_data_address = if raw_size > 0 { offset as u32 } else { 0 };
+            headers.push(header);
+
+            rva += align_up(header.virtual_size as usize, section_alignment);
+            offset += raw_size;
+        }
+
+        nt_header.file_header.num_of_sections = self.sections.len() as u16;
+        optional_header.size_of_headers = headers_size as u32;
+        optional_header.size_of_image = rva as u32;
+
+        (nt_header, headers)
+    }
+
+    /// Returns the bytes of an image made up of the built headers, followed by the
+    /// raw data of every section.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (nt_header, headers) = self.build();
+
+        let mut dos_header = zeroed::<DosHeader>();
+        dos_header.e_magic = DosHeader::DOS_SIGNATURE;
+        dos_header.e_lfanew = mem::size_of::<DosHeader>() as u32;
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(bytes_of(&dos_header));
+        bytes.extend_from_slice(bytes_of(&nt_header));
+        for header in &headers {
+            bytes.extend_from_slice(bytes_of(header));
+        }
+
+        for (header, (_, data)) in headers.iter().zip(&self.sections) {
+            if header.raw_data_size > 0 {
+                bytes.resize(header.raw_data_address as usize, 0);
+                bytes.extend_from_slice(data);
+            }
+        }
+        let size = align_up(
+            bytes.len(),
+            nt_header.optional_header.file_alignment as usize,
+        );
+        bytes.resize(
+            size.max(nt_header.optional_header.size_of_headers as usize),
+            0,
+        );
+
+        bytes
+    }
+}
+
+impl Default for NtHeaderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a zero initialized instance of a plain data structure
+fn zeroed<T: FromBytes>() -> T {
+    // Plain data structures are valid for any bit pattern
+    unsafe { mem::zeroed() }
+}
+
+/// Returns the bytes representing a header
+fn bytes_of<T: FromBytes>(value: &T) -> &[u8] {
+    // The headers are laid out exactly like their native structures, which contain
+    // no padding, so every byte of them is initialized
+    unsafe { slice::from_raw_parts((value as *const T).cast(), mem::size_of::<T>()) }
+}
@@ -1,8 +1,9 @@
-#![feature(error_in_core)]
 #![no_std]
 #![doc = include_str!("../README.md")]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod dir;
 pub mod error;
@@ -10,3 +11,4 @@ pub mod file;
 pub mod header;
 pub mod mem;
 pub mod section;
+pub mod symbol;
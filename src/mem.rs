@@ -1,9 +1,12 @@
 use crate::error::*;
-use alloc::string::ToString;
-use core::{ffi::CStr, mem};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{ffi::CStr, mem, ptr, slice, str};
 
 /// Aligns the value up to the specified alignment boundary
-pub fn algin_up(value: usize, align: usize) -> usize {
+pub fn align_up(value: usize, align: usize) -> usize {
     value + ((align - (value & (align - 1))) & (align - 1))
 }
 
@@ -20,15 +23,59 @@ pub fn str_from_bytes(bytes: &[u8]) -> Result<&str> {
         .map_err(|e| Error::Malformed(e.to_string()))
 }
 
+/// Creates a [`String`] from the specified bytes, which hold a UTF-16LE string.
+///
+/// # Errors
+///
+/// This function will return [`Error::Malformed`] if the bytes do not represent
+/// a valid, null-terminated UTF-16LE string, like one containing unpaired surrogates.
+pub fn utf16_from_bytes(bytes: &[u8]) -> Result<String> {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]));
+    let len = units.clone().position(|c| c == 0).ok_or_else(|| {
+        Error::Malformed("data provided does not contain a nul".into())
+    })?;
+
+    char::decode_utf16(units.take(len))
+        .map(|c| {
+            c.map_err(|e| {
+                Error::Malformed(format!(
+                    "invalid utf-16: unpaired surrogate {:#x}",
+                    e.unpaired_surrogate()
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Creates a [`str`] slice from the specified bytes, ignoring any trailing null padding.
+///
+/// # Errors
+///
+/// This function will return [`Error::Malformed`]
+/// if the bytes do not represent a valid UTF-8 string.
+pub fn str_from_padded_bytes(bytes: &[u8]) -> Result<&str> {
+    let len = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+
+    str::from_utf8(&bytes[..len]).map_err(|e| Error::Malformed(e.to_string()))
+}
+
 /// Used internally to describe a position within a [`ByteReader`]
 pub enum Pos {
-    /// Position is absolute
+    /// Position is absolute, respecting the relative position of the [`ByteReader`]
     Abs(usize),
     /// Position is relative to the current position
     Rel(usize),
 }
 
 /// Interface to safely read plain data which implements [`FromBytes`] from a [`u8`] slice
+///
+/// Only the headers of a [`PeView`](crate::file::PeView) and its sections are copied
+/// out with [`ByteReader::read_copied`]. The data directories, like the export, import,
+/// relocation and debug tables, are borrowed in place with [`ByteReader::read`], so
+/// parsing them fails with [`Error::Misaligned`] if the buffer of an image is not
+/// aligned to at least 8 bytes.
 pub struct ByteReader<'a> {
     bytes: &'a [u8],
     pos: usize,
@@ -63,7 +110,7 @@ impl<'a> ByteReader<'a> {
 
     /// Returns a reference to the remaining bytes of this [`ByteReader`].
     pub fn remaining_bytes(&self) -> &'a [u8] {
-        &self.bytes[self.pos..]
+        self.bytes.get(self.pos..).unwrap_or_default()
     }
 
     /// Returns a reference to the bytes of this [`ByteReader`], starting at the specified position
@@ -74,8 +121,8 @@ impl<'a> ByteReader<'a> {
     /// if the specified position is outside of the buffers bounds.
     pub fn bytes_at(&self, pos: usize) -> Result<&'a [u8]> {
         self.bytes
-            .get(self.pos_to_rel(pos)..)
-            .ok_or(Error::InsufficientBuffer)
+            .get(self.pos_to_rel(pos)?..)
+            .ok_or(Error::InsufficientBufferAt(pos))
     }
 
     /// Returns the relative position set by [`ByteReader::new_with_rel`] of this [`ByteReader`].
@@ -84,20 +131,60 @@ impl<'a> ByteReader<'a> {
     }
 
     /// Converts the specified RVA to a position within the buffer
-    pub fn pos_to_rel(&self, pos: usize) -> usize {
-        pos - self.rel_pos.unwrap_or(0)
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::InsufficientBuffer`]
+    /// if the specified RVA is located before the start of the buffer.
+    pub fn pos_to_rel(&self, pos: usize) -> Result<usize> {
+        pos.checked_sub(self.rel_pos.unwrap_or(0))
+            .ok_or(Error::InsufficientBuffer)
     }
 
-    /// Advances the internal data buffer to the specified position
+    /// Advances the internal data buffer to the specified position.
+    ///
+    /// Absolute positions are RVA's if the [`ByteReader`] was created by
+    /// [`ByteReader::new_with_rel`], while relative positions are always plain byte
+    /// counts from the current position.
+    ///
+    /// If the position can not be represented within the buffer, the buffer is
+    /// advanced to its end instead, which makes any following read fail.
+    /// Use [`ByteReader::try_skip_to`] to fail right away instead.
     pub fn skip_to(&mut self, pos: Pos) -> &mut Self {
-        match pos {
-            Pos::Abs(v) => self.pos = self.pos_to_rel(v),
-            Pos::Rel(v) => self.pos += self.pos_to_rel(v),
-        }
+        let pos = match pos {
+            Pos::Abs(v) => self.pos_to_rel(v),
+            Pos::Rel(v) => self.pos.checked_add(v).ok_or(Error::InsufficientBuffer),
+        };
+        self.pos = pos.unwrap_or(self.bytes.len());
 
         self
     }
 
+    /// Advances the internal data buffer to the specified position like
+    /// [`ByteReader::skip_to`], but fails if the position is outside of the buffer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::InsufficientBuffer`] if the position can not
+    /// be represented within the buffer, in which case the position is left unchanged.
+    pub fn try_skip_to(&mut self, pos: Pos) -> Result<&mut Self> {
+        let (rel, at) = match pos {
+            Pos::Abs(v) => (self.pos_to_rel(v).ok(), v),
+            Pos::Rel(v) => (
+                self.pos.checked_add(v),
+                self.absolute_position().saturating_add(v),
+            ),
+        };
+
+        match rel {
+            Some(rel) if rel <= self.bytes.len() => {
+                self.pos = rel;
+                Ok(self)
+            }
+            _ => Err(Error::InsufficientBufferAt(at)),
+        }
+    }
+
     /// Reads a plain data structure implementing [`FromBytes`] from the current position
     ///
     /// # Errors
@@ -110,11 +197,12 @@ impl<'a> ByteReader<'a> {
         T: FromBytes,
     {
         // Read the structure at the current position
-        let res = T::from_bytes(
-            self.bytes
-                .get(self.pos..)
-                .ok_or(Error::InsufficientBuffer)?,
-        )?;
+        let res = self
+            .bytes
+            .get(self.pos..)
+            .ok_or(Error::InsufficientBuffer)
+            .and_then(T::from_bytes)
+            .map_err(|e| e.at(self.absolute_position()))?;
 
         // Advance the buffer by the size of the read structure
         self.pos += mem::size_of::<T>();
@@ -122,6 +210,57 @@ impl<'a> ByteReader<'a> {
         Ok(res)
     }
 
+    /// Reads a copy of a plain data structure implementing [`FromBytes`] from the
+    /// current position, which unlike [`ByteReader::read`] may be misaligned
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the current position is invalid
+    /// or the remaining bytes are too small for the requested structure
+    pub fn read_copied<T>(&mut self) -> Result<T>
+    where
+        T: FromBytes,
+    {
+        // Read the structure at the current position
+        let res = self
+            .bytes
+            .get(self.pos..)
+            .ok_or(Error::InsufficientBuffer)
+            .and_then(T::from_bytes_copied)
+            .map_err(|e| e.at(self.absolute_position()))?;
+
+        // Advance the buffer by the size of the read structure
+        self.pos += mem::size_of::<T>();
+
+        Ok(res)
+    }
+
+    /// Reads `count` contiguous plain data structures implementing [`FromBytes`]
+    /// from the current position
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the current position is invalid
+    /// or the bytes at the current position do not have the right memory layout for the requested
+    /// structures
+    pub fn read_slice<T>(&mut self, count: usize) -> Result<&'a [T]>
+    where
+        T: FromBytes,
+    {
+        // Read the structures at the current position
+        let res = self
+            .bytes
+            .get(self.pos..)
+            .ok_or(Error::InsufficientBuffer)
+            .and_then(|bytes| T::slice_from_bytes(bytes, count))
+            .map_err(|e| e.at(self.absolute_position()))?;
+
+        // Advance the buffer by the size of the read structures
+        self.pos += mem::size_of_val(res);
+
+        Ok(res)
+    }
+
     /// Reads a plain data structure implementing [`FromBytes`] from the specified position
     ///
     /// # Errors
@@ -133,11 +272,21 @@ impl<'a> ByteReader<'a> {
     where
         T: FromBytes,
     {
-        T::from_bytes(
-            self.bytes
-                .get(self.pos_to_rel(pos)..)
-                .ok_or(Error::InsufficientBuffer)?,
-        )
+        self.bytes_at(pos)
+            .and_then(T::from_bytes)
+            .map_err(|e| e.at(pos))
+    }
+
+    /// Returns the current position of this [`ByteReader`] within its bytes.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the current position of this [`ByteReader`], including its relative position.
+    ///
+    /// This is the position reported by errors of the read operations.
+    pub fn absolute_position(&self) -> usize {
+        self.pos + self.rel_pos.unwrap_or(0)
     }
 }
 
@@ -166,13 +315,63 @@ pub unsafe trait FromBytes: Copy + 'static {
         }
 
         // Check if the buffer is aligned correctly
-        if bytes.as_ptr() as usize % mem::align_of::<Self>() != 0 {
+        if !(bytes.as_ptr() as usize).is_multiple_of(mem::align_of::<Self>()) {
             return Err(Error::Misaligned);
         }
 
         // Interpret the bytes as a reference to [`Self`]
         Ok(unsafe { &*(bytes.as_ptr().cast()) })
     }
+
+    /// Returns a copy of a single instance of [`Self`] represented by the specified bytes,
+    /// regardless of the memory alignment of the buffer.
+    /// Does not check for correct endianness.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::InsufficientBuffer`]
+    /// if the buffer is not big enough to read the requested structure.
+    fn from_bytes_copied(bytes: &[u8]) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        // Check if the buffer is large enough
+        if bytes.len() < mem::size_of::<Self>() {
+            return Err(Error::InsufficientBuffer);
+        }
+
+        // Copy the bytes into an owned instance of [`Self`]
+        Ok(unsafe { ptr::read_unaligned(bytes.as_ptr().cast()) })
+    }
+
+    /// Returns a slice of `count` contiguous instances of [`Self`] represented by the specified bytes.
+    /// Does not check for correct endianness.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The buffer is not big enough to read the requested structures ([`Error::InsufficientBuffer`])
+    /// - The buffers memory alignment is not ABI complaint with the requested structure ([`Error::Misaligned`])
+    fn slice_from_bytes(bytes: &[u8], count: usize) -> Result<&[Self]>
+    where
+        Self: Sized,
+    {
+        // Check if the buffer is large enough to hold all elements
+        let size = mem::size_of::<Self>()
+            .checked_mul(count)
+            .ok_or(Error::InsufficientBuffer)?;
+        if bytes.len() < size {
+            return Err(Error::InsufficientBuffer);
+        }
+
+        // Check if the buffer is aligned correctly
+        if !(bytes.as_ptr() as usize).is_multiple_of(mem::align_of::<Self>()) {
+            return Err(Error::Misaligned);
+        }
+
+        // Interpret the bytes as a slice of [`Self`]
+        Ok(unsafe { slice::from_raw_parts(bytes.as_ptr().cast(), count) })
+    }
 }
 
 #[macro_export]
@@ -186,3 +385,7 @@ macro_rules! impl_from_bytes {
 
 // Implement [`FromBytes`] for the default unsigned integer types
 impl_from_bytes!(u8, u16, u32, u64);
+
+// Fixed-size arrays of plain data are plain data themselves. Their alignment equals the
+// alignment of the element type, so the checks done by [`FromBytes::from_bytes`] still hold
+unsafe impl<T, const N: usize> FromBytes for [T; N] where T: FromBytes {}
@@ -1,28 +1,92 @@
-use crate::{error::*, file::PeAddr, header::*, mem::ByteReader};
-use core::str;
+use crate::{
+    error::*,
+    file::{Checks, ParseOptions, PeAddr},
+    header::*,
+    mem::*,
+    symbol::StringTable,
+};
+use alloc::{string::String, vec::Vec};
+use core::{iter::FusedIterator, mem, ops::Range};
+
+/// Difference between a section of two PE32+ files, see
+/// [`crate::file::PeView::diff_sections`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SectionDiff {
+    /// Index of the section within the section table
+    pub index: usize,
+    /// Ranges of RVAs within the section whose bytes differ
+    pub ranges: Vec<Range<u32>>,
+}
+
+/// Section with a conventional name, see [`crate::file::PeView::well_known`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WellKnownSection {
+    /// Executable code
+    Text,
+    /// Initialized read-write data
+    Data,
+    /// Initialized read-only data
+    RData,
+    /// Base relocations
+    Reloc,
+    /// Resources
+    Rsrc,
+    /// Export tables
+    EData,
+    /// Import tables
+    IData,
+    /// Exception information
+    PData,
+}
+
+impl WellKnownSection {
+    /// Returns the conventional name of this [`WellKnownSection`].
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Text => ".text",
+            Self::Data => ".data",
+            Self::RData => ".rdata",
+            Self::Reloc => ".reloc",
+            Self::Rsrc => ".rsrc",
+            Self::EData => ".edata",
+            Self::IData => ".idata",
+            Self::PData => ".pdata",
+        }
+    }
+}
 
 /// Section of a PE32+ file
+///
+/// If the virtual size of a section exceeds the size of its raw data, the loader
+/// zero-fills the remaining part of the section when mapping it. This part has no
+/// backing bytes in the file, see [`Section::is_bss_rva`].
 pub struct Section<'a> {
-    header: &'a SectionHeader,
+    header: SectionHeader,
+    header_bytes: &'a [u8],
     data: Option<ByteReader<'a>>,
 }
 
 impl<'a> Section<'a> {
-    /// Creates the [`Section`] of a PE32+ which is represented by the specified header
+    /// Creates the [`Section`] of a PE32+ which is represented by the header at the
+    /// start of the specified header bytes
     ///
     /// # Errors
     ///
-    /// This function will return an error if the byte buffer does not hold
-    /// a valid and complete section
-    pub fn parse(bytes: &'a [u8], header: &'a SectionHeader) -> Result<Self> {
+    /// This function will return an error if the header bytes are too small to hold
+    /// a [`SectionHeader`], or the byte buffer does not hold a valid and complete
+    /// section
+    pub fn parse(bytes: &'a [u8], header_bytes: &'a [u8]) -> Result<Self> {
+        let (header, header_bytes) = Self::read_header(header_bytes)?;
+
         // Check if section contains any raw data
         let data = if header.raw_data_size > 0 {
             // Get a slice of the PE32+ bytes which holds the sections raw data
+            let end = header
+                .raw_data_address
+                .checked_add(header.raw_data_size)
+                .ok_or(Error::InsufficientBuffer)?;
             let bytes = bytes
-                .get(
-                    header.raw_data_address as _
-                        ..(header.raw_data_address + header.raw_data_size) as _,
-                )
+                .get(header.raw_data_address as _..end as _)
                 .ok_or(Error::InsufficientBuffer)?;
 
             Some(ByteReader::new_with_rel(bytes, header.virtual_address as _))
@@ -30,12 +94,70 @@ impl<'a> Section<'a> {
             None
         };
 
-        Ok(Self { header, data })
+        Ok(Self {
+            header,
+            header_bytes,
+            data,
+        })
+    }
+
+    /// Creates the [`Section`] of a PE32+ image mapped into memory, which is represented
+    /// by the header at the start of the specified header bytes
+    ///
+    /// The data of the section is read from its RVA, spanning its virtual size.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the header bytes are too small to hold
+    /// a [`SectionHeader`], or the byte buffer does not hold a valid and complete
+    /// section
+    pub fn parse_mapped(bytes: &'a [u8], header_bytes: &'a [u8]) -> Result<Self> {
+        let (header, header_bytes) = Self::read_header(header_bytes)?;
+
+        let size = match header.virtual_size {
+            0 => header.raw_data_size,
+            v => v,
+        };
+
+        // Check if section contains any data
+        let data = if size > 0 {
+            // Get a slice of the mapped image which holds the section
+            let end = header
+                .virtual_address
+                .checked_add(size)
+                .ok_or(Error::InsufficientBuffer)?;
+            let bytes = bytes
+                .get(header.virtual_address as _..end as _)
+                .ok_or(Error::InsufficientBuffer)?;
+
+            Some(ByteReader::new_with_rel(bytes, header.virtual_address as _))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            header,
+            header_bytes,
+            data,
+        })
+    }
+
+    /// Internal method for reading the [`SectionHeader`] at the start of the specified
+    /// bytes, returning it along with the bytes it was read from
+    fn read_header(bytes: &'a [u8]) -> Result<(SectionHeader, &'a [u8])> {
+        let header = SectionHeader::from_bytes_copied(bytes)?;
+        Ok((header, &bytes[..mem::size_of::<SectionHeader>()]))
     }
 
     /// Returns a reference to the header of this [`Section`].
     pub fn header(&self) -> &SectionHeader {
-        self.header
+        &self.header
+    }
+
+    /// Returns the raw bytes of the header of this [`Section`], as stored in the
+    /// section table.
+    pub fn header_bytes(&self) -> &'a [u8] {
+        self.header_bytes
     }
 
     /// Returns a reference to the data of this [`Section`].
@@ -53,13 +175,161 @@ impl<'a> Section<'a> {
     /// passing the header to [`Section::parse`].
     ///
     /// The above is automatically done by [`crate::file::PeView::parse`].
+    /// Use [`Section::try_name`] for headers which have not been validated.
     pub fn name(&self) -> &str {
-        str::from_utf8(self.header.name.as_slice()).unwrap()
+        self.try_name().unwrap()
+    }
+
+    /// Returns a reference to the name of this [`Section`], without its trailing
+    /// null padding.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::Malformed`]
+    /// if the raw bytes in the sections header are not a valid UTF-8 string.
+    pub fn try_name(&self) -> Result<&str> {
+        str_from_padded_bytes(self.header.name.as_slice())
+    }
+
+    /// Returns the full name of this [`Section`].
+    ///
+    /// Names longer than eight characters are stored as `/offset`,
+    /// which is resolved through the specified [`StringTable`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the name is not a valid UTF-8 string
+    /// or its string table offset is invalid.
+    pub fn long_name(&self, strings: &StringTable<'a>) -> Result<&str> {
+        let name = self.try_name()?;
+
+        match name.strip_prefix('/').map(str::parse::<u32>) {
+            Some(Ok(offset)) => strings.get(offset),
+            _ => Ok(name),
+        }
     }
 
     /// Checks if the specified flag is contained in the headers characteristics.
     pub fn has_flag(&self, flag: SectionFlags) -> bool {
-        self.header.characteristics & flag as u32 == 1
+        self.characteristics().contains(flag)
+    }
+
+    /// Returns the characteristics of this [`Section`] as a set of flags.
+    pub fn characteristics(&self) -> SectionCharacteristics {
+        SectionCharacteristics(self.header.characteristics)
+    }
+
+    /// Returns the names of the characteristics of this [`Section`], see
+    /// [`SectionCharacteristics::flags_string`].
+    pub fn flags_string(&self) -> String {
+        self.characteristics().flags_string()
+    }
+
+    /// Returns the part of the raw data of this [`Section`] which is actually mapped,
+    /// excluding the padding up to the file alignment.
+    ///
+    /// This is at most `virtual_size` bytes long. A virtual size of zero is treated as
+    /// unspecified, in which case the whole raw data is returned.
+    ///
+    /// Returns [`None`] if the section has no raw data.
+    pub fn virtual_data(&self) -> Option<&'a [u8]> {
+        let bytes = self.data.as_ref()?.bytes();
+
+        match self.header.virtual_size as usize {
+            0 => Some(bytes),
+            size => Some(&bytes[..size.min(bytes.len())]),
+        }
+    }
+
+    /// Returns an iterator over the raw data of this [`Section`] in chunks of `row`
+    /// bytes, each paired with the RVA of its first byte.
+    ///
+    /// The last chunk is shorter if the size of the raw data is not a multiple of `row`.
+    /// Returns [`None`] if the section has no raw data or `row` is zero.
+    pub fn chunks(
+        &self,
+        row: usize,
+    ) -> Option<impl Iterator<Item = (u32, &'a [u8])>> {
+        let bytes = self.data.as_ref()?.bytes();
+        let rva = self.header.virtual_address;
+
+        (row > 0).then(|| {
+            bytes
+                .chunks(row)
+                .enumerate()
+                .map(move |(i, chunk)| (rva.wrapping_add((i * row) as u32), chunk))
+        })
+    }
+
+    /// Returns the Shannon entropy of the raw data of this [`Section`] in bits per byte,
+    /// ranging from 0.0 to 8.0.
+    ///
+    /// Packed or encrypted sections usually have an entropy close to 8.0.
+    /// Returns [`None`] if the section has no raw data.
+    pub fn entropy(&self) -> Option<f64> {
+        let bytes = self.data.as_ref()?.bytes();
+
+        let mut counts = [0usize; 256];
+        for &b in bytes {
+            counts[b as usize] += 1;
+        }
+
+        let len = bytes.len() as f64;
+        Some(
+            counts
+                .iter()
+                .filter(|&&c| c > 0)
+                .map(|&c| {
+                    let p = c as f64 / len;
+                    -p * log2(p)
+                })
+                .sum(),
+        )
+    }
+
+    /// Returns the raw data of this [`Section`], starting at the specified RVA.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The RVA is within the zero-filled part of the section ([`Error::UninitializedData`])
+    /// - The section has no raw data ([`Error::SectionEmpty`])
+    /// - The RVA is outside of the sections raw data ([`Error::InsufficientBuffer`])
+    pub fn bytes_at_rva(&self, rva: u32) -> Result<&'a [u8]> {
+        if self.is_bss_rva(rva) {
+            return Err(Error::UninitializedData);
+        }
+
+        self.data
+            .as_ref()
+            .ok_or(Error::SectionEmpty)?
+            .bytes_at(rva as _)
+    }
+
+    /// Checks if the specified RVA is within the virtual range of this [`Section`],
+    /// but past the end of its raw data.
+    ///
+    /// This part of the section is zero-filled by the loader. Sections of a mapped
+    /// image, see [`Section::parse_mapped`], already contain it.
+    pub fn is_bss_rva(&self, rva: u32) -> bool {
+        let size = self.data.as_ref().map_or(0, |d| d.bytes().len());
+
+        self.contains_addr(PeAddr::Rva(rva))
+            && (rva - self.header.virtual_address) as usize >= size
+    }
+
+    /// Returns the number of bytes between the end of the raw data of this [`Section`]
+    /// and the specified file offset, where the raw data of the next section starts.
+    pub fn file_padding(&self, next_raw_start: u32) -> u32 {
+        next_raw_start.saturating_sub(self.file_range().end)
+    }
+
+    /// Returns the number of bytes of this [`Section`] which are zero-filled by the
+    /// loader, see [`Section::is_bss_rva`].
+    pub fn virtual_gap(&self) -> u32 {
+        self.header
+            .virtual_size
+            .saturating_sub(self.header.raw_data_size)
     }
 
     /// Checks if the section has no raw data.
@@ -67,9 +337,66 @@ impl<'a> Section<'a> {
         self.data.is_none()
     }
 
+    /// Returns the range of RVAs this [`Section`] occupies when mapped.
+    ///
+    /// The end of the range saturates at the end of the 32-bit address space.
+    pub fn rva_range(&self) -> Range<u32> {
+        let start = self.header.virtual_address;
+        start..start.saturating_add(self.header.virtual_size)
+    }
+
+    /// Returns the range of file offsets holding the raw data of this [`Section`].
+    ///
+    /// The end of the range saturates at the end of the 32-bit address space.
+    pub fn file_range(&self) -> Range<u32> {
+        let start = self.header.raw_data_address;
+        start..start.saturating_add(self.header.raw_data_size)
+    }
+
+    /// Returns the ranges of RVAs at which the data of this [`Section`] differs from
+    /// the data of the specified one, with both mapped at the RVA of this section.
+    ///
+    /// The data of both sections is compared up to their virtual size, so bytes
+    /// past the end of either one are treated as zero-filled by the loader. The
+    /// comparison stops at the end of the 32-bit address space, which the end of
+    /// a range can not exceed.
+    pub fn diff(&self, other: &Section<'_>) -> Vec<Range<u32>> {
+        let (a, b) = (
+            self.virtual_data().unwrap_or_default(),
+            other.virtual_data().unwrap_or_default(),
+        );
+        let mut ranges = Vec::<Range<u32>>::new();
+
+        for i in 0..a.len().max(b.len()) {
+            if a.get(i).unwrap_or(&0) == b.get(i).unwrap_or(&0) {
+                continue;
+            }
+
+            // The end of a range containing the last RVA would overflow
+            let Some(rva) = u32::try_from(i)
+                .ok()
+                .and_then(|i| self.header.virtual_address.checked_add(i))
+                .filter(|&rva| rva != u32::MAX)
+            else {
+                break;
+            };
+
+            // Extend the previous range if it ends right before this byte
+            match ranges.last_mut() {
+                Some(r) if r.end == rva => r.end = rva + 1,
+                _ => ranges.push(rva..rva + 1),
+            }
+        }
+
+        ranges
+    }
+
     /// Checks if the specified address is contained within the sections raw data.
+    ///
+    /// Unlike [`Section::rva_range`] and [`Section::file_range`], a range exceeding the
+    /// 32-bit address space does not contain any address.
     pub fn contains_addr(&self, addr: PeAddr) -> bool {
-        let range = match addr {
+        let (start, size, addr) = match addr {
             PeAddr::Rva(rva) => {
                 (self.header.virtual_address, self.header.virtual_size, rva)
             }
@@ -78,6 +405,133 @@ impl<'a> Section<'a> {
             }
         };
 
-        (range.0..range.0 + range.1).contains(&range.2)
+        start
+            .checked_add(size)
+            .is_some_and(|end| (start..end).contains(&addr))
+    }
+}
+
+/// Computes the binary logarithm of a positive, normal value
+///
+/// `core` does not provide it, so the value is split into its exponent and a mantissa
+/// in `[1, 2)`, whose logarithm is computed by the series of `atanh`.
+fn log2(value: f64) -> f64 {
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7FF) as i64 - 1023;
+    let mantissa = f64::from_bits((bits & !(0x7FF << 52)) | (1023 << 52));
+
+    // ln(m) = 2 * atanh((m - 1) / (m + 1)), which converges fast as |y| <= 1/3
+    let y = (mantissa - 1.0) / (mantissa + 1.0);
+    let (mut term, mut ln) = (y, 0.0);
+    for n in (1..40).step_by(2) {
+        ln += term / n as f64;
+        term *= y * y;
     }
+
+    exponent as f64 + 2.0 * ln / core::f64::consts::LN_2
 }
+
+/// Mutable section of a PE32+ file, see [`crate::file::PeViewMut`]
+pub struct SectionMut<'a> {
+    header: &'a SectionHeader,
+    data: &'a mut [u8],
+}
+
+impl<'a> SectionMut<'a> {
+    pub fn new(header: &'a SectionHeader, data: &'a mut [u8]) -> Self {
+        Self { header, data }
+    }
+
+    /// Returns a reference to the header of this [`SectionMut`].
+    pub fn header(&self) -> &SectionHeader {
+        self.header
+    }
+
+    /// Returns a reference to the raw data of this [`SectionMut`].
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Returns a mutable reference to the raw data of this [`SectionMut`].
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+}
+
+/// Iterator which lazily parses and validates the sections of a PE32+ file
+pub struct SectionIter<'a> {
+    bytes: &'a [u8],
+    headers: ByteReader<'a>,
+    nt_header: NtHeader,
+    remaining: u16,
+    opts: ParseOptions,
+    skipped: Checks,
+}
+
+impl<'a> SectionIter<'a> {
+    pub(crate) fn new(
+        bytes: &'a [u8],
+        headers: ByteReader<'a>,
+        nt_header: NtHeader,
+        opts: ParseOptions,
+        skipped: Checks,
+    ) -> Self {
+        Self {
+            bytes,
+            headers,
+            nt_header,
+            remaining: nt_header.file_header.num_of_sections,
+            opts,
+            skipped,
+        }
+    }
+
+    /// Returns the checks which failed, but were skipped so far.
+    pub fn skipped_checks(&self) -> Checks {
+        self.skipped
+    }
+}
+
+impl<'a> Iterator for SectionIter<'a> {
+    type Item = Result<Section<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Check if the iterator reached the end of the section table
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        match (|| {
+            // Read and validate the next section header
+            let header_bytes = self.headers.remaining_bytes();
+            let header = self.headers.read_copied::<SectionHeader>()?;
+            if self.opts.validate {
+                header.validate_with(
+                    &self.nt_header.optional_header,
+                    &self.opts,
+                    &mut self.skipped,
+                )?;
+            }
+
+            let section = if self.opts.mapped {
+                Section::parse_mapped(self.bytes, header_bytes)?
+            } else {
+                Section::parse(self.bytes, header_bytes)?
+            };
+
+            // The name is always checked, as accessing it relies on it being valid
+            section.try_name()?;
+
+            Ok(section)
+        })() {
+            Ok(v) => Some(Ok(v)),
+            Err(e) => {
+                self.remaining = 0;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for SectionIter<'a> {}
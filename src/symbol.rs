@@ -0,0 +1,202 @@
+use crate::{error::*, impl_from_bytes, mem::*};
+use alloc::format;
+use core::{iter::FusedIterator, mem};
+
+/// COFF string table following the symbol table
+#[derive(Clone, Copy)]
+pub struct StringTable<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> StringTable<'a> {
+    /// Creates a [`StringTable`] from the specified bytes, starting at its size field.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::InsufficientBuffer`]
+    /// if the bytes are smaller than the declared size of the table.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self> {
+        // The table is not necessarily aligned, so its size is read byte-wise
+        let size = bytes
+            .get(..mem::size_of::<u32>())
+            .ok_or(Error::InsufficientBuffer)?;
+        let size = u32::from_le_bytes([size[0], size[1], size[2], size[3]]);
+
+        Ok(Self {
+            bytes: bytes
+                .get(..size as usize)
+                .ok_or(Error::InsufficientBuffer)?,
+        })
+    }
+
+    /// Returns the declared size of this [`StringTable`], including the size field itself.
+    pub fn size(&self) -> u32 {
+        self.bytes.len() as u32
+    }
+
+    /// Returns the null-terminated string at the specified offset.
+    /// Offsets are relative to the start of the table.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the offset points outside the declared
+    /// size of the table, or the bytes at the offset are not a valid, null-terminated
+    /// UTF-8 string.
+    pub fn get(&self, offset: u32) -> Result<&'a str> {
+        if (offset as usize) < mem::size_of::<u32>() || offset >= self.size() {
+            return Error::make_malformed::<Self, _>(format!(
+                "has invalid offset ({offset:#x})"
+            ));
+        }
+
+        str_from_bytes(&self.bytes[offset as usize..])
+    }
+}
+
+/// Entry of the COFF symbol table
+pub struct Symbol<'a> {
+    entry: &'a SymbolEntry,
+    aux: &'a [u8],
+    strings: Option<StringTable<'a>>,
+}
+
+impl<'a> Symbol<'a> {
+    pub fn new(
+        entry: &'a SymbolEntry,
+        aux: &'a [u8],
+        strings: Option<StringTable<'a>>,
+    ) -> Self {
+        Self {
+            entry,
+            aux,
+            strings,
+        }
+    }
+
+    /// Returns a reference to the native entry of this [`Symbol`].
+    pub fn entry(&self) -> &'a SymbolEntry {
+        self.entry
+    }
+
+    /// Returns the parsed name of this [`Symbol`].
+    ///
+    /// Short names are stored inline, while longer ones are resolved
+    /// through the string table following the symbol table.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the name is not a valid UTF-8 string
+    /// or its string table offset is out of bounds.
+    pub fn name(&self) -> Result<&'a str> {
+        let name = &self.entry.name;
+
+        // A name starting with four zero bytes holds an offset into the string table
+        if name[..4] == [0; 4] {
+            let offset = u32::from_le_bytes([name[4], name[5], name[6], name[7]]);
+            self.strings.ok_or(Error::InsufficientBuffer)?.get(offset)
+        } else {
+            str_from_padded_bytes(name)
+        }
+    }
+
+    /// Returns the `value` field of the [`SymbolEntry`].
+    pub fn value(&self) -> u32 {
+        self.entry.value
+    }
+
+    /// Returns the `section_number` field of the [`SymbolEntry`].
+    ///
+    /// Positive values are 1-based indices into the section table.
+    pub fn section_number(&self) -> i16 {
+        self.entry.section_number
+    }
+
+    /// Returns the `typ` field of the [`SymbolEntry`].
+    pub fn typ(&self) -> u16 {
+        self.entry.typ
+    }
+
+    /// Returns the `storage_class` field of the [`SymbolEntry`].
+    pub fn storage_class(&self) -> u8 {
+        self.entry.storage_class
+    }
+
+    /// Returns the raw bytes of the auxiliary records following this [`Symbol`].
+    pub fn aux_data(&self) -> &'a [u8] {
+        self.aux
+    }
+}
+
+/// Iterator over the COFF symbol table
+pub struct SymbolTable<'a> {
+    data: ByteReader<'a>,
+    strings: Option<StringTable<'a>>,
+    remaining: u32,
+}
+
+impl<'a> SymbolTable<'a> {
+    /// Creates a [`SymbolTable`] over `count` symbol records contained in the specified bytes,
+    /// resolving long names through the specified [`StringTable`].
+    pub fn new(
+        bytes: &'a [u8],
+        strings: Option<StringTable<'a>>,
+        count: u32,
+    ) -> Self {
+        Self {
+            data: ByteReader::new(bytes),
+            strings,
+            remaining: count,
+        }
+    }
+}
+
+impl<'a> Iterator for SymbolTable<'a> {
+    type Item = Result<Symbol<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Check if the iterator reached the end of the table
+        if self.remaining == 0 {
+            return None;
+        }
+
+        match (|| {
+            // Read the next symbol record
+            let entry = self.data.read::<SymbolEntry>()?;
+            self.remaining -= 1;
+
+            // Skip over the auxiliary records so the next read stays aligned to a symbol
+            let aux_count = (entry.num_of_aux_symbols as u32).min(self.remaining);
+            let aux = self
+                .data
+                .remaining_bytes()
+                .get(..aux_count as usize * mem::size_of::<SymbolEntry>())
+                .ok_or(Error::InsufficientBuffer)?;
+            self.data.skip_to(Pos::Rel(aux.len()));
+            self.remaining -= aux_count;
+
+            Ok(Symbol::new(entry, aux, self.strings))
+        })() {
+            Ok(v) => Some(Ok(v)),
+            Err(e) => {
+                self.remaining = 0;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for SymbolTable<'a> {}
+
+/// Native structure define by [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#coff-symbol-table)
+#[derive(Clone, Copy)]
+#[repr(C, packed)]
+pub struct SymbolEntry {
+    pub name: [u8; 8],
+    pub value: u32,
+    pub section_number: i16,
+    pub typ: u16,
+    pub storage_class: u8,
+    pub num_of_aux_symbols: u8,
+}
+
+impl_from_bytes!(SymbolEntry);
@@ -1,8 +1,36 @@
 use peview::{
-    dir::{ExportValue, Import, Relocation},
-    file::PeView,
+    dir::{
+        Arm64XFixup, Arm64XFixupKind, Certificate, CertificateTable, DataDirectory,
+        DataDirectoryTable, DataDirectoryType, DebugDirectoryEntry, DebugEntry,
+        DynamicRelocation, DynamicRelocationTable, ExportTable, ExportValue, Import,
+        ImportName, ImportTable, PogoEntry, Relocation, RelocationKind,
+        RelocationTable, VersionInfo,
+    },
+    error::ValidationRule,
+    file::{Check, OwnedPeView, ParseOptions, PeAddr, PeView, PeViewMut},
+    header::{
+        Bitness, DllCharacteristics, DllFlags, DosHeader, FileHeader, FileMachine,
+        NtHeader, NtHeaderBuilder, OptionalHeader, SectionFlags, SectionHeader,
+    },
+    mem::{utf16_from_bytes, ByteReader, FromBytes, Pos},
+    section::{Section, SectionDiff, WellKnownSection},
+    symbol::StringTable,
 };
-use std::{error::Error, fs::File, io::Read};
+use std::{
+    collections::BTreeSet, error::Error, fs::File, io::Read, iter::FusedIterator,
+    mem,
+};
+
+// Characteristics of the usual sections of an image
+const TEXT: u32 = SectionFlags::CntCode as u32
+    | SectionFlags::Execute as u32
+    | SectionFlags::Read as u32;
+const RDATA: u32 = SectionFlags::CntInitData as u32 | SectionFlags::Read as u32;
+const DATA: u32 = RDATA | SectionFlags::Write as u32;
+const BSS: u32 = SectionFlags::CntUninitData as u32
+    | SectionFlags::Read as u32
+    | SectionFlags::Write as u32;
+const RELOC: u32 = RDATA | SectionFlags::Discardable as u32;
 
 #[test]
 fn it_parses_relocations() -> Result<(), Box<dyn Error>> {
@@ -20,12 +48,15 @@ fn it_parses_relocations() -> Result<(), Box<dyn Error>> {
             assert_eq!(block.page_rva(), 0x3000);
         }
 
+        let page_rva = block.page_rva();
         for j in block {
-            if let Relocation::Dir64(v) = j? {
+            let reloc = j?;
+            if reloc.kind == RelocationKind::Dir64 {
                 reloc_count += 1;
 
                 if reloc_count == CHECKED_INDEX {
-                    assert_eq!(v, 0x18);
+                    assert_eq!(reloc.offset, 0x18);
+                    assert_eq!(reloc.rva, page_rva + 0x18);
                 }
             }
         }
@@ -36,6 +67,21 @@ fn it_parses_relocations() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn it_counts_relocations() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    let mut count = 0;
+    for block in pe.relocations()? {
+        count += block?.count();
+    }
+    assert_eq!(pe.relocation_count()?, count);
+
+    Ok(())
+}
+
 #[test]
 fn it_parses_imports() -> Result<(), Box<dyn Error>> {
     let mut buf = Vec::new();
@@ -69,6 +115,160 @@ fn it_parses_imports() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn it_summarizes_imports() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    let summary = pe.import_summary()?;
+    assert_eq!(summary.len(), pe.imports()?.count());
+
+    for ((name, symbols), module) in summary.into_iter().zip(pe.imports()?) {
+        let module = module?;
+        assert_eq!(name, module.name()?);
+
+        let expected = module.map(|i| i.map(ImportName::from));
+        assert_eq!(symbols, expected.collect::<Result<Vec<_>, _>>()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_pe32_imports() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x2000;
+
+    // IDT with a single module, followed by the 32-bit ILT, the module name and the H/NT
+    let mut buf = Vec::new();
+    for field in [RVA + 0x28, 0, 0, RVA + 0x34, RVA + 0x28, 0, 0, 0, 0, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [0x8000_0007u32, RVA + 0x40, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    buf.extend_from_slice(b"A.dll\0\0\0\0\0\0\0");
+    buf.extend_from_slice(&2u16.to_le_bytes());
+    buf.extend_from_slice(b"Foo\0");
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: 40,
+    };
+
+    let mut modules = ImportTable::new(&buf, &dir).with_bitness(Bitness::Bits32);
+    let mut module = modules.next().unwrap()?;
+    assert_eq!(module.name()?, "A.dll");
+    assert!(matches!(module.next().unwrap()?, Import::Ordinal(7)));
+    assert!(matches!(module.next().unwrap()?, Import::Name(2, "Foo")));
+    assert!(module.next().is_none());
+    assert!(modules.next().is_none());
+
+    let mut modules = ImportTable::new(&buf, &dir).with_bitness(Bitness::Bits32);
+    let mut module = modules.next().unwrap()?;
+    assert!(matches!(
+        module.find_by_name("Foo").unwrap()?,
+        Import::Name(2, "Foo")
+    ));
+    assert!(module.find_by_name("Foo").is_none());
+
+    // The unbound IAT equals the ILT
+    let module = ImportTable::new(&buf, &dir)
+        .with_bitness(Bitness::Bits32)
+        .next()
+        .unwrap()?;
+    let entries = module.entries().collect::<Result<Vec<_>, _>>()?;
+    assert!(matches!(
+        entries[..],
+        [
+            (Import::Ordinal(7), Some(0x8000_0007)),
+            (Import::Name(2, "Foo"), Some(0x2040))
+        ]
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn it_pairs_import_thunks() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x2000;
+
+    // IDT with a single module, whose bound IAT differs from its ILT
+    let mut buf = Vec::new();
+    for field in [RVA + 0x28, 0, 0, RVA + 0x58, RVA + 0x40, 0, 0, 0, 0, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [0x8000_0000_0000_0007u64, RVA as u64 + 0x60, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [0x7FF6_1234_0000u64, 0x7FF6_1234_0010, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    buf.extend_from_slice(b"A.dll\0\0\0");
+    buf.extend_from_slice(&2u16.to_le_bytes());
+    buf.extend_from_slice(b"Foo\0");
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: 40,
+    };
+    let thunks = |buf: &[u8]| -> Result<Vec<_>, Box<dyn Error>> {
+        let module = ImportTable::new(buf, &dir).next().ok_or("no module")??;
+        Ok(module
+            .thunks()
+            .map(|t| t.map(|(entry, address)| (entry.raw(), address)))
+            .collect::<Result<Vec<_>, _>>()?)
+    };
+    assert_eq!(
+        thunks(&buf)?,
+        [
+            (0x8000_0000_0000_0007, 0x7FF6_1234_0000),
+            (RVA as u64 + 0x60, 0x7FF6_1234_0010)
+        ]
+    );
+
+    // Without an ILT, the IAT is paired with itself
+    buf[..4].copy_from_slice(&0u32.to_le_bytes());
+    assert_eq!(
+        thunks(&buf)?,
+        [
+            (0x7FF6_1234_0000, 0x7FF6_1234_0000),
+            (0x7FF6_1234_0010, 0x7FF6_1234_0010)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn it_falls_back_to_iat() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x2000;
+
+    // IDT with a single module without ILT, followed by the IAT, the name and the H/NT
+    let mut buf = Vec::new();
+    for field in [0, 0, 0, RVA + 0x38, RVA + 0x28, 0, 0, 0, 0, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [RVA as u64 + 0x40, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    buf.extend_from_slice(b"B.dll\0\0\0");
+    buf.extend_from_slice(&3u16.to_le_bytes());
+    buf.extend_from_slice(b"Bar\0");
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: 40,
+    };
+
+    let mut module = ImportTable::new(&buf, &dir).next().unwrap()?;
+    assert!(module.uses_iat_fallback());
+    assert!(matches!(module.next().unwrap()?, Import::Name(3, "Bar")));
+    assert!(module.next().is_none());
+
+    Ok(())
+}
+
 #[test]
 fn it_parses_exports() -> Result<(), Box<dyn Error>> {
     let mut buf = Vec::new();
@@ -108,7 +308,2355 @@ fn it_parses_cert() -> Result<(), Box<dyn Error>> {
         assert_eq!(cert.value().bytes().len(), 0x2560);
         assert_eq!(cert.revision(), 0x200);
         assert_eq!(cert.typ(), 2);
+        assert_eq!(cert.pkcs7_der().map(|d| d[0]), Some(0x30));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_certificate_data() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    // The directory addresses the certificates by file offset
+    let dir = pe.directory(DataDirectoryType::CertificateTable).unwrap();
+    let data = pe.certificate_data()?;
+    assert_eq!(data, &buf[dir.addr as usize..][..dir.size as usize]);
+
+    Ok(())
+}
+
+#[test]
+fn it_counts_certificates() -> Result<(), Box<dyn Error>> {
+    let builder = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10]);
+
+    let buf = builder.to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert!(!pe.is_signed());
+    assert_eq!(pe.certificate_count()?, 0);
+
+    // Two certificates appended to the image
+    let mut certs = Vec::new();
+    for typ in [Certificate::TYPE_PKCS_SIGNED_DATA, Certificate::TYPE_X509] {
+        certs.extend_from_slice(&16u32.to_le_bytes());
+        certs.extend_from_slice(&0x200u16.to_le_bytes());
+        certs.extend_from_slice(&typ.to_le_bytes());
+        certs.extend_from_slice(&[0xAA; 8]);
+    }
+
+    let dir = DataDirectory {
+        addr: buf.len() as u32,
+        size: certs.len() as u32,
+    };
+    let mut buf = builder
+        .data_directory(DataDirectoryType::CertificateTable, dir)
+        .to_bytes();
+    buf.extend_from_slice(&certs);
+
+    let pe = PeView::parse(&buf)?;
+    assert!(pe.is_signed());
+    assert_eq!(pe.certificate_count()?, 2);
+
+    // Only signed data is handed out as PKCS#7
+    let certs = pe.certificates()?.collect_all()?;
+    assert_eq!(certs[0].pkcs7_der(), Some(&[0xAA; 8][..]));
+    assert_eq!(certs[1].pkcs7_der(), None);
+
+    Ok(())
+}
+
+#[test]
+fn it_rejects_truncated_certificates() -> Result<(), Box<dyn Error>> {
+    // A certificate shorter than its head, then one exceeding the table
+    for length in [4u32, 0x100] {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&length.to_le_bytes());
+        buf.extend_from_slice(&0x200u16.to_le_bytes());
+        buf.extend_from_slice(&2u16.to_le_bytes());
+        buf.extend_from_slice(&[0; 8]);
+
+        let dir = DataDirectory {
+            addr: 0x400,
+            size: buf.len() as u32,
+        };
+
+        let mut certs = CertificateTable::new(&buf, &dir);
+        assert!(certs.next().unwrap().is_err());
+        assert!(certs.next().is_none());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_arrays() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    // Data directories are located at the end of the optional header
+    let optional_header = &pe.nt_header().optional_header;
+    let pos = pe.dos_header().e_lfanew as usize
+        + mem::size_of::<u32>()
+        + mem::size_of::<FileHeader>()
+        + pe.nt_header().file_header.size_of_optional_header as usize
+        - mem::size_of_val(&optional_header.data_directories);
+
+    let dirs = ByteReader::new(&buf).read_at::<[DataDirectory; 16]>(pos)?;
+    for (a, b) in dirs.iter().zip(optional_header.data_directories.iter()) {
+        assert_eq!(a.addr, b.addr);
+        assert_eq!(a.size, b.size);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_rejects_overflowing_directories() {
+    let dir = DataDirectory {
+        addr: 0xFFFFFF00,
+        size: 0x200,
+    };
+    assert!(!dir.contains_addr(0xFFFFFF00));
+    assert!(!dir.contains_addr(0xFFFFFFFF));
+    assert!(!dir.contains_addr(0x50));
+
+    let dir = DataDirectory {
+        addr: 0x1000,
+        size: 0,
+    };
+    assert!(!dir.contains_addr(0x1000));
+}
+
+#[test]
+fn it_parses_leniently() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+
+    // Set the reserved 'win32_version_value' field of the optional header
+    let pos = PeView::parse(&buf)?.dos_header().e_lfanew as usize
+        + mem::size_of::<u32>()
+        + mem::size_of::<FileHeader>()
+        + mem::offset_of!(OptionalHeader, win32_version_value);
+    buf[pos] = 1;
+
+    assert!(PeView::parse(&buf).is_err());
+
+    let pe = PeView::parse_lenient(&buf)?;
+    assert!(pe.skipped_checks().contains(Check::ReservedField));
+    assert!(!pe.skipped_checks().contains(Check::ImageBase));
+
+    Ok(())
+}
+
+#[test]
+fn it_bounds_section_count() -> Result<(), Box<dyn Error>> {
+    let builder = NtHeaderBuilder::new().section(".text", TEXT, 0, &[0xC3; 0x10]);
+    assert_eq!(PeView::parse(&builder.to_bytes())?.sections().len(), 1);
+
+    // Exceeding the limit of the loader is only rejected by strict parsing
+    let buf = (0..96)
+        .fold(builder, |b, _| b.section(".bss", BSS, 0x10, &[]))
+        .to_bytes();
+    assert!(PeView::parse(&buf).is_err());
+
+    let pe = PeView::parse_lenient(&buf)?;
+    assert_eq!(pe.sections().len(), 97);
+    assert!(pe.skipped_checks().contains(Check::SectionCount));
+
+    Ok(())
+}
+
+#[test]
+fn it_skips_relocation_blocks() -> Result<(), Box<dyn Error>> {
+    // Two blocks, each holding a single entry followed by padding
+    let mut buf = Vec::new();
+    for (page_rva, entry) in [(0x1000u32, 0xA010u16), (0x2000, 0xA020)] {
+        buf.extend_from_slice(&page_rva.to_le_bytes());
+        buf.extend_from_slice(&12u32.to_le_bytes());
+        buf.extend_from_slice(&entry.to_le_bytes());
+        buf.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    let dir = DataDirectory {
+        addr: 0x3000,
+        size: buf.len() as u32,
+    };
+
+    let mut blocks = RelocationTable::new(&buf, &dir);
+    for (page_rva, offset) in [(0x1000, 0x10), (0x2000, 0x20)] {
+        let mut block = blocks.next().unwrap()?;
+        assert_eq!(block.page_rva(), page_rva);
+        assert_eq!(
+            block.next().unwrap()?,
+            Relocation {
+                kind: RelocationKind::Dir64,
+                offset,
+                rva: page_rva + offset as u32,
+            }
+        );
+        assert_eq!(block.next().unwrap()?.kind, RelocationKind::Absolute);
+        assert!(block.next().is_none());
+    }
+    assert!(blocks.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn it_skips_relative_to_the_position() -> Result<(), Box<dyn Error>> {
+    let buf = (0u32..8).flat_map(u32::to_le_bytes).collect::<Vec<_>>();
+
+    // Relative skips are not rebased like the RVAs of a relative reader
+    let mut data = ByteReader::new_with_rel(&buf, 0x1000);
+    assert_eq!(data.skip_to(Pos::Rel(4)).position(), 4);
+    assert_eq!(data.read::<u32>()?, &1);
+    assert_eq!(data.skip_to(Pos::Rel(8)).read::<u32>()?, &4);
+    assert_eq!(data.skip_to(Pos::Abs(0x1004)).read::<u32>()?, &1);
+    assert_eq!(data.absolute_position(), 0x1008);
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_slices() -> Result<(), Box<dyn Error>> {
+    let buf = (0u32..8).flat_map(u32::to_le_bytes).collect::<Vec<_>>();
+    let mut data = ByteReader::new(&buf);
+
+    // Empty slices can be read anywhere, without advancing
+    assert!(data.read_slice::<u32>(0)?.is_empty());
+    assert_eq!(data.position(), 0);
+
+    assert_eq!(data.read_slice::<u32>(3)?, [0, 1, 2]);
+    assert_eq!(data.position(), 12);
+
+    // Slices exceeding the buffer are rejected, without advancing
+    assert!(data
+        .read_slice::<u32>(6)
+        .is_err_and(|e| e.is_insufficient_buffer()));
+    assert!(data.read_slice::<u32>(usize::MAX).is_err());
+    assert_eq!(data.position(), 12);
+
+    // The remaining bytes can be read exactly
+    assert_eq!(data.read_slice::<u32>(5)?, [3, 4, 5, 6, 7]);
+    assert_eq!(data.position(), buf.len());
+    assert!(data.read_slice::<u32>(0)?.is_empty());
+    assert!(data.read_slice::<u32>(1).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_keeps_unknown_relocations() -> Result<(), Box<dyn Error>> {
+    // A reserved type followed by a regular entry
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&0x1000u32.to_le_bytes());
+    buf.extend_from_slice(&12u32.to_le_bytes());
+    buf.extend_from_slice(&0x6008u16.to_le_bytes());
+    buf.extend_from_slice(&0xA010u16.to_le_bytes());
+
+    let dir = DataDirectory {
+        addr: 0x3000,
+        size: buf.len() as u32,
+    };
+
+    let block = RelocationTable::new(&buf, &dir).next().unwrap()?;
+    let kinds = block
+        .map(|r| r.map(|r| (r.kind, r.rva)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        kinds,
+        [
+            (RelocationKind::Unknown(6), 0x1008),
+            (RelocationKind::Dir64, 0x1010)
+        ]
+    );
+    assert_eq!(
+        kinds.iter().map(|(k, _)| k.code()).collect::<Vec<_>>(),
+        [0x6, 0xA]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn it_reverses_relocation_blocks() -> Result<(), Box<dyn Error>> {
+    // Two blocks with a single entry each
+    let mut buf = Vec::new();
+    for page_rva in [0x1000u32, 0x2000] {
+        buf.extend_from_slice(&page_rva.to_le_bytes());
+        buf.extend_from_slice(&12u32.to_le_bytes());
+        buf.extend_from_slice(&0xA010u16.to_le_bytes());
+        buf.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    let dir = DataDirectory {
+        addr: 0x3000,
+        size: buf.len() as u32,
+    };
+
+    let pages = RelocationTable::new(&buf, &dir)
+        .rev()?
+        .map(|b| b.page_rva())
+        .collect::<Vec<_>>();
+    assert_eq!(pages, [0x2000, 0x1000]);
+
+    // Malformed blocks are reported before any block is returned
+    buf[16..20].copy_from_slice(&0x100u32.to_le_bytes());
+    assert!(RelocationTable::new(&buf, &dir).rev().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_rejects_truncated_relocation_blocks() -> Result<(), Box<dyn Error>> {
+    // A block declaring more entries than the table holds, then one smaller than its head
+    for block_size in [0x100u32, 4] {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&0x1000u32.to_le_bytes());
+        buf.extend_from_slice(&block_size.to_le_bytes());
+        buf.extend_from_slice(&0xA010u16.to_le_bytes());
+        buf.extend_from_slice(&0u16.to_le_bytes());
+
+        let dir = DataDirectory {
+            addr: 0x3000,
+            size: buf.len() as u32,
+        };
+
+        let mut blocks = RelocationTable::new(&buf, &dir);
+        assert!(blocks.next().unwrap().is_err());
+        assert!(blocks.next().is_none());
+    }
+
+    // Every truncation of a valid table must parse without panicking
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&0x1000u32.to_le_bytes());
+    buf.extend_from_slice(&12u32.to_le_bytes());
+    buf.extend_from_slice(&0xA010u16.to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes());
+
+    for len in 0..buf.len() {
+        let dir = DataDirectory {
+            addr: 0x3000,
+            size: len as u32,
+        };
+
+        for block in RelocationTable::new(&buf[..len], &dir).flatten() {
+            assert!(block.entry_count() <= 2);
+            block.for_each(drop);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_resolves_relocation_sections() -> Result<(), Box<dyn Error>> {
+    // One block relocating .text, padded by an absolute entry, and one outside the image
+    let mut reloc = Vec::new();
+    for (page_rva, entries) in
+        [(0x1000u32, [0xA008u16, 0]), (0x8000, [0xA000, 0xA010])]
+    {
+        reloc.extend_from_slice(&page_rva.to_le_bytes());
+        reloc.extend_from_slice(&12u32.to_le_bytes());
+        entries
+            .iter()
+            .for_each(|e| reloc.extend_from_slice(&e.to_le_bytes()));
+    }
+
+    let dir = DataDirectory {
+        addr: 0x2000,
+        size: reloc.len() as u32,
+    };
+    let buf = NtHeaderBuilder::new()
+        .data_directory(DataDirectoryType::RelocationTable, dir)
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".reloc", RELOC, 0, &reloc)
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    let relocs = pe
+        .relocations_with_section()?
+        .map(|r| r.map(|(s, rva)| (s.map(|s| s.name()), rva)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        relocs,
+        [(Some(".text"), 0x1008), (None, 0x8000), (None, 0x8010)]
+    );
+
+    assert_eq!(pe.table::<RelocationTable>()?.count(), 2);
+    assert!(pe.table::<ExportTable>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_lists_data_directories() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    let present = pe
+        .data_directories()
+        .map(|(typ, _)| typ as usize)
+        .collect::<Vec<_>>();
+
+    for typ in [
+        DataDirectoryType::ExportTable,
+        DataDirectoryType::ImportTable,
+        DataDirectoryType::ExceptionTable,
+        DataDirectoryType::RelocationTable,
+        DataDirectoryType::Debug,
+        DataDirectoryType::CertificateTable,
+    ] {
+        assert!(present.contains(&(typ as usize)));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_respects_directory_count() -> Result<(), Box<dyn Error>> {
+    let dir = |addr, size| DataDirectory { addr, size };
+    let builder = NtHeaderBuilder::new()
+        .data_directory(DataDirectoryType::ExportTable, dir(0x1000, 8))
+        .data_directory(DataDirectoryType::Debug, dir(0x1008, 8))
+        .optional_header(|h| h.num_of_rva_and_sizes = 6)
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10]);
+
+    let buf = builder.to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert_eq!(pe.nt_header().num_of_data_directories(), 6);
+    assert_eq!(pe.num_of_rva_and_sizes(), 6);
+    assert!(pe.directory(DataDirectoryType::ExportTable).is_some());
+    assert!(pe.directory(DataDirectoryType::Debug).is_none());
+    assert_eq!(pe.data_directories().count(), 1);
+
+    // Directories beyond the declared size of the optional header are not declared
+    let (mut nt_header, _) = builder.build();
+    nt_header.file_header.size_of_optional_header = 112 + 2 * 8;
+    assert_eq!(nt_header.num_of_data_directories(), 2);
+    assert!(nt_header
+        .data_directory(DataDirectoryType::ResourceTable)
+        .is_none());
+
+    // Directories past the declared count are never read, even if they are filled
+    let buf = builder
+        .data_directory(DataDirectoryType::CLRRuntimeHeader, dir(0x1010, 8))
+        .optional_header(|h| h.num_of_rva_and_sizes = 2)
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert!(pe.directory(DataDirectoryType::CLRRuntimeHeader).is_none());
+    assert!(!pe.is_dotnet());
+
+    Ok(())
+}
+
+#[test]
+fn it_indexes_data_directories() {
+    assert_eq!(DataDirectoryType::ExportTable as usize, 0);
+    assert_eq!(DataDirectoryType::CertificateTable as usize, 4);
+    assert_eq!(DataDirectoryType::CLRRuntimeHeader as usize, 14);
+    assert_eq!(DataDirectoryType::Reserved as usize, 15);
+
+    for (i, typ) in DataDirectoryType::ALL.into_iter().enumerate() {
+        assert_eq!(typ as usize, i);
+    }
+
+    assert_eq!(ExportTable::typ(), DataDirectoryType::ExportTable);
+    assert_eq!(RelocationTable::typ(), DataDirectoryType::RelocationTable);
+    assert_ne!(ImportTable::typ(), DataDirectoryType::ImportAddressTable);
+}
+
+#[test]
+fn it_finds_sections_by_name() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    // Names are stored null padded, which must not be part of the name
+    let text = pe.section_by_name(".text").unwrap();
+    assert_eq!(&text.header().name[..6], b".text\0");
+    assert!(pe.sections().iter().all(|s| !s.name().ends_with('\0')));
+
+    Ok(())
+}
+
+#[test]
+fn it_finds_sections_by_addr() -> Result<(), Box<dyn Error>> {
+    let mut buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0x3000, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    fn name_at<'a>(pe: &'a PeView, rva: u32) -> Option<&'a str> {
+        pe.section_by_addr(PeAddr::Rva(rva)).map(|s| s.name())
+    }
+    assert_eq!(name_at(&pe, 0x1000), Some(".text"));
+    assert_eq!(name_at(&pe, 0x3FFF), Some(".text"));
+    assert_eq!(name_at(&pe, 0x4000), Some(".data"));
+    assert_eq!(name_at(&pe, 0xFFF), None);
+    assert_eq!(name_at(&pe, 0x4010), None);
+
+    // Move .data into .text, so the last section starting before an RVA past the end
+    // of .data does not contain it, while the overlapping .text still does
+    let offset = pe.section_headers_offset() + mem::size_of::<SectionHeader>() + 12;
+    buf[offset..offset + 4].copy_from_slice(&0x1800u32.to_le_bytes());
+    let pe = PeView::parse_with(&buf, &ParseOptions::unchecked())?;
+
+    assert_eq!(name_at(&pe, 0x1800), Some(".data"));
+    assert_eq!(name_at(&pe, 0x2000), Some(".text"));
+    assert_eq!(name_at(&pe, 0x4000), None);
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_section_bytes_at_rvas() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".data", DATA, 0x300, &[0xAB; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    // The raw data is padded to 0x200 bytes, the rest of the section is zero-filled
+    let data = &pe.sections()[0];
+    assert!(!data.is_bss_rva(0x11FF));
+    assert_eq!(data.bytes_at_rva(0x1000)?[..0x10], [0xAB; 0x10]);
+    assert_eq!(data.bytes_at_rva(0x11FF)?.len(), 1);
+
+    for rva in [0x1200, 0x12FF] {
+        assert!(data.is_bss_rva(rva));
+        assert!(matches!(
+            data.bytes_at_rva(rva),
+            Err(peview::error::Error::UninitializedData)
+        ));
     }
 
+    // Past the virtual range, the RVA is neither zero-filled nor readable
+    assert!(!data.is_bss_rva(0x1300));
+    assert!(data.bytes_at_rva(0x1300).is_err());
+    assert!(!data.is_bss_rva(0xFFF));
+
+    // Sections without raw data are zero-filled entirely
+    let bss = &pe.sections()[1];
+    assert!(bss.is_bss_rva(0x2000));
+    assert!(bss.is_bss_rva(0x20FF));
+    assert!(!bss.is_bss_rva(0x2100));
+    assert!(matches!(
+        bss.bytes_at_rva(0x2100),
+        Err(peview::error::Error::SectionEmpty)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn it_clamps_section_data() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    let mut padded = 0;
+    for section in pe.sections().iter().filter(|s| !s.empty()) {
+        let header = section.header();
+        let data = section.virtual_data().unwrap();
+        assert_eq!(
+            data.len(),
+            header.virtual_size.min(header.raw_data_size) as usize
+        );
+
+        if data.len() < section.data().as_ref().unwrap().bytes().len() {
+            padded += 1;
+        }
+    }
+    assert!(padded > 0);
+
+    Ok(())
+}
+
+#[test]
+fn it_checks_file_characteristics() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    let characteristics = pe.file_characteristics();
+    assert!(characteristics.is_executable_image());
+    assert!(characteristics.large_address_aware());
+    assert!(!characteristics.is_dll());
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_ordinal_exports() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    let mut exports = pe.exports()?;
+    let base = exports.export_table()?.ordinal_base;
+    let (funcs, names) = (exports.func_count()?, exports.name_count()?);
+
+    // Ordinals are assigned by EAT index, regardless of an export being named
+    let (mut named, mut count) = (0, 0);
+    let mut last = None;
+    for export in exports {
+        let export = export?;
+        assert!((base..base + funcs).contains(&(export.ordinal as u32)));
+        assert!(last < Some(export.ordinal));
+        last = Some(export.ordinal);
+        named += export.name.is_some() as u32;
+        count += 1;
+    }
+    assert_eq!(named, names);
+
+    let mut nameless = 0;
+    for export in pe.exports()?.ordinals_only() {
+        assert!(export?.name.is_none());
+        nameless += 1;
+    }
+    assert_eq!(nameless, count - names);
+
+    Ok(())
+}
+
+#[test]
+fn it_skips_unused_export_slots() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x1000;
+
+    // EDT followed by an EAT with two unused slots, the ENPT, the EOT and the names
+    let edt = [
+        0,
+        0,
+        0x2_0001,
+        RVA + 0x43,
+        1,
+        4,
+        1,
+        RVA + 0x28,
+        RVA + 0x38,
+        RVA + 0x3c,
+    ];
+    let mut buf = Vec::new();
+    for field in edt {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [0x500u32, 0, 0, 0x600, RVA + 0x3e] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    buf.extend_from_slice(&3u16.to_le_bytes());
+    buf.extend_from_slice(b"Last\0m.dll\0");
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: buf.len() as u32,
+    };
+
+    let mut exports = ExportTable::new(&buf, &dir);
+    assert_eq!(exports.module_name()?, "m.dll");
+    assert_eq!(exports.ordinal_base()?, 1);
+    assert_eq!(exports.version()?, (1, 2));
+    assert_eq!(exports.function_rvas()?, [0x500, 0, 0, 0x600]);
+
+    let exports = exports
+        .map(|e| e.map(|e| (e.ordinal, e.name)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(exports, [(1, None), (4, Some("Last"))]);
+
+    // A names table requires an ordinals table
+    buf[36..40].fill(0);
+    assert!(matches!(
+        ExportTable::new(&buf, &dir).export_table(),
+        Err(peview::error::Error::Validation {
+            rule: ValidationRule::NameOrOrdinalTable,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn it_counts_forwarded_exports() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x1000;
+
+    // EDT followed by an EAT whose first entry points into the directory, making it
+    // a forwarder, the ENPT, the EOT, the names and the forwarder string
+    let edt = [
+        0,
+        0,
+        0,
+        RVA + 0x36,
+        1,
+        2,
+        1,
+        RVA + 0x28,
+        RVA + 0x30,
+        RVA + 0x34,
+    ];
+    let mut buf = Vec::new();
+    for field in edt {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [RVA + 0x3c, 0x600, RVA + 0x42] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    buf.extend_from_slice(&1u16.to_le_bytes());
+    buf.extend_from_slice(b"m.dll\0m.Fwd\0Last\0");
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: buf.len() as u32,
+    };
+
+    let mut exports = ExportTable::new(&buf, &dir);
+    assert_eq!(exports.forwarded_count()?, 1);
+
+    let exports = exports
+        .map(|e| e.map(|e| (e.ordinal, e.name, e.is_forwarded())))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(exports, [(1, None, true), (2, Some("Last"), false)]);
+
+    Ok(())
+}
+
+#[test]
+fn it_names_exports_of_unsorted_ordinal_tables() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x1000;
+    const COUNT: u32 = 0x4000;
+
+    // EDT followed by the EAT, the ENPT, the EOT in reverse order and the names
+    let (names_rva, ordinals_rva) = (RVA + 0x28 + COUNT * 4, RVA + 0x28 + COUNT * 8);
+    let strings_rva = ordinals_rva + COUNT * 2;
+    let edt = [
+        0,
+        0,
+        0,
+        strings_rva,
+        1,
+        COUNT,
+        COUNT,
+        RVA + 0x28,
+        names_rva,
+        ordinals_rva,
+    ];
+    let mut buf = Vec::new();
+    for field in edt {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for i in 0..COUNT {
+        buf.extend_from_slice(&(0x10_0000 + i).to_le_bytes());
+    }
+    for i in 0..COUNT {
+        buf.extend_from_slice(&(strings_rva + i * 6).to_le_bytes());
+    }
+    for i in 0..COUNT {
+        buf.extend_from_slice(&((COUNT - 1 - i) as u16).to_le_bytes());
+    }
+    for i in 0..COUNT {
+        buf.extend_from_slice(format!("{i:05}\0").as_bytes());
+    }
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: buf.len() as u32,
+    };
+
+    let mut count = 0;
+    for (i, export) in ExportTable::new(&buf, &dir).enumerate() {
+        let name = format!("{:05}", COUNT as usize - 1 - i);
+        assert_eq!(export?.name, Some(name.as_str()));
+        count += 1;
+    }
+    assert_eq!(count, COUNT);
+
+    let export = ExportTable::new(&buf, &dir).resolve(&Import::Ordinal(1))?;
+    let name = format!("{:05}", COUNT - 1);
+    assert_eq!(export.and_then(|e| e.name), Some(name.as_str()));
+
+    Ok(())
+}
+
+#[test]
+fn it_resolves_imports() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x1000;
+
+    // EDT followed by the EAT, the ENPT, the EOT and the names
+    let edt = [
+        0,
+        0,
+        0,
+        RVA + 0x40,
+        1,
+        2,
+        2,
+        RVA + 0x28,
+        RVA + 0x30,
+        RVA + 0x38,
+    ];
+    let mut buf = Vec::new();
+    for field in edt
+        .into_iter()
+        .chain([0x600, 0x700, RVA + 0x3c, RVA + 0x3e])
+    {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    buf.extend_from_slice(&0u16.to_le_bytes());
+    buf.extend_from_slice(&1u16.to_le_bytes());
+    buf.extend_from_slice(b"A\0B\0m.dll\0");
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: buf.len() as u32,
+    };
+    let mut exports = ExportTable::new(&buf, &dir);
+    let mut resolve = |import| -> Result<_, Box<dyn Error>> {
+        Ok(exports.resolve(&import)?.map(|e| {
+            (
+                e.ordinal,
+                e.name,
+                matches!(e.value, ExportValue::Rva(v) if v != 0),
+            )
+        }))
+    };
+
+    // A matching hint, a stale hint and an out of range hint resolve the same way
+    for hint in [1, 0, 7] {
+        assert_eq!(
+            resolve(Import::Name(hint, "B"))?,
+            Some((2, Some("B"), true))
+        );
+    }
+    assert_eq!(resolve(Import::Ordinal(1))?, Some((1, Some("A"), true)));
+    assert_eq!(resolve(Import::Name(0, "C"))?, None);
+    assert_eq!(resolve(Import::Ordinal(3))?, None);
+
+    Ok(())
+}
+
+#[test]
+fn it_builds_headers() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .optional_header(|h| h.address_of_entry_point = 0x1000)
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    assert_eq!(pe.size_of_headers(), 0x200);
+    assert_eq!(pe.size_of_image(), 0x3000);
+    assert_eq!(pe.image_base(), 0x140000000);
+    assert_eq!(pe.address_of_entry_point(), 0x1000);
+    assert_eq!(pe.loader_flags(), 0);
+
+    let text = pe.section_by_name(".text").unwrap();
+    assert_eq!(text.header().virtual_address, 0x1000);
+    assert_eq!(text.virtual_data().unwrap(), [0xC3; 0x10]);
+
+    assert_eq!(
+        pe.well_known(WellKnownSection::Text).map(|s| s.name()),
+        Some(".text")
+    );
+    assert!(pe.well_known(WellKnownSection::Reloc).is_none());
+
+    let offset = pe.section_headers_offset();
+    assert_eq!(text.header_bytes(), &buf[offset..offset + 40]);
+    assert_eq!(text.header_bytes()[..8], *b".text\0\0\0");
+
+    // Sections parsed directly keep the header bytes they were parsed from
+    let direct = Section::parse(&buf, &buf[offset..])?;
+    assert_eq!(direct.header_bytes(), text.header_bytes());
+    assert_eq!(direct.header().virtual_address, 0x1000);
+    let mapped = Section::parse_mapped(&[0; 0x1010], &buf[offset..offset + 40])?;
+    assert_eq!(mapped.header_bytes(), text.header_bytes());
+    assert!(Section::parse(&buf, &buf[offset..offset + 39]).is_err());
+
+    let bss = pe.section_by_name(".bss").unwrap();
+    assert_eq!(bss.header().virtual_address, 0x2000);
+    assert!(bss.data().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn it_patches_sections() -> Result<(), Box<dyn Error>> {
+    let mut buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+    let mut pe = PeViewMut::parse(&mut buf)?;
+
+    pe.write_at_rva(0x1004, &[0x90; 2])?;
+    pe.section_by_name_mut(".text").unwrap().data_mut()[0] = 0xCC;
+    assert!(pe.write_at_rva(0x2000, &[0]).is_err());
+    assert!(pe.write_at_rva(0x11FF, &[0; 2]).is_err());
+    assert!(pe.section_by_name_mut(".bss").is_none());
+
+    let view = pe.view()?;
+    let offset = view.rva_to_offset(0x1000).unwrap() as usize;
+    assert_eq!(offset, 0x200);
+    assert_eq!(view.rva_to_offset(0x2000), None);
+    assert_eq!(
+        buf[offset..offset + 7],
+        [0xCC, 0xC3, 0xC3, 0xC3, 0x90, 0x90, 0xC3]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_mapped_images() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+
+    // Map the image like the loader does
+    let pe = PeView::parse(&buf)?;
+    let opt = &pe.nt_header().optional_header;
+    let mut image = vec![0u8; opt.size_of_image as usize];
+    image[..opt.size_of_headers as usize]
+        .copy_from_slice(&buf[..opt.size_of_headers as usize]);
+    for header in pe.sections().iter().map(|s| s.header()) {
+        let (rva, raw) = (
+            header.virtual_address as usize,
+            header.raw_data_address as usize,
+        );
+        let size = header.raw_data_size as usize;
+        image[rva..rva + size].copy_from_slice(&buf[raw..raw + size]);
+    }
+
+    let mapped = PeView::parse_mapped(&image)?;
+    let text = mapped.section_by_name(".text").unwrap();
+    assert_eq!(text.data().as_ref().unwrap().bytes()[..0x10], [0xC3; 0x10]);
+    assert_eq!(mapped.rva_to_offset(0x1004), Some(0x1004));
+    assert_eq!(mapped.rva_to_offset(0x2010), Some(0x2010));
+    assert!(mapped.certificate_data().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_indexes_sections() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    assert_eq!(pe.section(1).map(|s| s.name()), Some(".data"));
+    assert_eq!(pe.section_by_number(1).map(|s| s.name()), Some(".text"));
+    assert!(pe.section(2).is_none());
+    assert!(pe.section_by_number(0).is_none());
+    assert!(pe.section_by_number(-1).is_none());
+    assert!(pe.section_by_number(3).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn it_chunks_sections() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    let text = &pe.sections()[0];
+
+    // The raw data is padded to the file alignment of 0x200 bytes
+    let rows = text.chunks(0x180).unwrap().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[0].0, rows[0].1.len()), (0x1000, 0x180));
+    assert_eq!((rows[1].0, rows[1].1.len()), (0x1180, 0x80));
+    assert_eq!(rows[0].1[..0x10], [0xC3; 0x10]);
+
+    let rows = text.chunks(0x1000).unwrap().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 1);
+    assert_eq!((rows[0].0, rows[0].1.len()), (0x1000, 0x200));
+
+    assert!(text.chunks(0).is_none());
+    assert!(pe.sections()[1].chunks(0x10).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn it_measures_section_padding() -> Result<(), Box<dyn Error>> {
+    let mut buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+
+    // Move the raw data of .data back by one file alignment
+    let offset = PeView::parse(&buf)?.section_headers_offset()
+        + mem::size_of::<SectionHeader>()
+        + 20;
+    buf[offset..offset + 4].copy_from_slice(&0x600u32.to_le_bytes());
+    buf.resize(buf.len() + 0x200, 0);
+
+    let pe = PeView::parse(&buf)?;
+    assert_eq!(pe.section_padding(0), Some(0x200));
+    assert_eq!(pe.section_padding(1), Some(0));
+    assert_eq!(pe.section_padding(2), Some(0));
+    assert_eq!(pe.section_padding(3), None);
+
+    let data = &pe.sections()[1];
+    assert_eq!(data.rva_range(), 0x2000..0x2010);
+    assert_eq!(data.file_range(), 0x600..0x800);
+    assert!(data.contains_addr(PeAddr::FilePtr(0x7FF)));
+    assert!(!data.contains_addr(PeAddr::Rva(0x2010)));
+
+    // Ranges exceeding the address space saturate, but do not contain any address
+    let offset = pe.section_headers_offset();
+    let mut header = buf[offset..offset + 40].to_vec();
+    header[8..12].copy_from_slice(&0x10u32.to_le_bytes());
+    header[12..16].copy_from_slice(&0xFFFF_FFE0u32.to_le_bytes());
+    let top = Section::parse(&buf, &header)?;
+    assert!(top.contains_addr(PeAddr::Rva(0xFFFF_FFEF)));
+
+    header[8..12].copy_from_slice(&0x100u32.to_le_bytes());
+    let top = Section::parse(&buf, &header)?;
+    assert_eq!(top.rva_range(), 0xFFFF_FFE0..u32::MAX);
+    assert!(!top.contains_addr(PeAddr::Rva(0xFFFF_FFEF)));
+
+    let gaps = pe.sections().iter().map(|s| s.virtual_gap());
+    assert!(gaps.eq([0, 0, 0x100]));
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_wide_strings() -> Result<(), Box<dyn Error>> {
+    let wide = |s: &str| {
+        s.encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>()
+    };
+
+    let mut data = wide("Version\0");
+    data.extend_from_slice(&[0x00, 0xD8, 0x41, 0x00, 0x00, 0x00]);
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".rsrc", RDATA, 0, &data)
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    assert_eq!(pe.wide_string_at_rva(0x2000)?, "Version");
+    assert_eq!(pe.wide_string_at_rva(0x2006)?, "sion");
+    assert!(pe.wide_string_at_rva(0x2010).is_err());
+    assert!(pe.wide_string_at_rva(0x3000).is_err());
+
+    assert_eq!(utf16_from_bytes(&wide("\u{1F600}\0"))?, "\u{1F600}");
+    assert!(utf16_from_bytes(&wide("abc")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_version_info() -> Result<(), Box<dyn Error>> {
+    let wide = |s: &str| {
+        s.encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>()
+    };
+    let block =
+        |key: &str, typ: u16, value_len: u16, value: &[u8], children: &[u8]| {
+            let mut buf = [0u16, value_len, typ]
+                .into_iter()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<_>>();
+            buf.extend(wide(key));
+            buf.extend([0, 0]);
+            buf.resize(buf.len().next_multiple_of(4), 0);
+            buf.extend_from_slice(value);
+            buf.resize(buf.len().next_multiple_of(4), 0);
+            buf.extend_from_slice(children);
+
+            let len = buf.len() as u16;
+            buf[..2].copy_from_slice(&len.to_le_bytes());
+            buf.resize(buf.len().next_multiple_of(4), 0);
+            buf
+        };
+
+    let fixed = [
+        0xFEEF04BD, 0x10000, 0x10002, 0x30004, 0x50006, 0, 0x3F, 0x2, 4,
+    ]
+    .into_iter()
+    .chain([0; 4])
+    .flat_map(u32::to_le_bytes)
+    .collect::<Vec<_>>();
+    let strings = [
+        block("CompanyName", 1, 5, &wide("Acme\0"), &[]),
+        block("Comments", 1, 0, &[], &[]),
+    ]
+    .concat();
+    let table = block("040904b0", 1, 0, &[], &strings);
+    let children = [
+        block("StringFileInfo", 1, 0, &[], &table),
+        block(
+            "VarFileInfo",
+            1,
+            0,
+            &[],
+            &block("Translation", 0, 4, &[9, 4, 0xB0, 4], &[]),
+        ),
+    ]
+    .concat();
+    let version = block("VS_VERSION_INFO", 0, 52, &fixed, &children);
+
+    // Resource tree of type, name and language directories leading to the version
+    let mut rsrc = [
+        [0, 0, 0, 0x10000, 16, 0x80000018],
+        [0, 0, 0, 0x10000, 1, 0x80000030],
+        [0, 0, 0, 0x10000, 0x409, 0x48],
+    ]
+    .into_iter()
+    .flatten()
+    .chain([0x2058, version.len() as u32, 0, 0])
+    .flat_map(u32::to_le_bytes)
+    .collect::<Vec<_>>();
+    rsrc.extend_from_slice(&version);
+
+    let dir = DataDirectory {
+        addr: 0x2000,
+        size: rsrc.len() as u32,
+    };
+    let builder = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".rsrc", RDATA, 0, &rsrc);
+
+    let buf = builder.to_bytes();
+    assert!(PeView::parse(&buf)?.version_info()?.is_none());
+
+    let buf = builder
+        .data_directory(DataDirectoryType::ResourceTable, dir)
+        .to_bytes();
+    let info = PeView::parse(&buf)?.version_info()?.unwrap();
+    let fixed = info.fixed().unwrap();
+    assert_eq!(fixed.file_version(), [1, 2, 3, 4]);
+    assert_eq!(fixed.product_version(), [5, 6, 0, 0]);
+    assert_eq!(fixed.flags(), 0x2);
+    assert_eq!(fixed.file_os, 4);
+    assert_eq!(info.string("CompanyName"), Some("Acme"));
+    assert_eq!(info.string("Comments"), Some(""));
+    assert_eq!(info.strings().len(), 2);
+
+    // Resource data carries no alignment guarantee
+    let unaligned = [&[0][..], &version].concat();
+    let info = VersionInfo::parse(&unaligned[1..])?;
+    assert_eq!(info.fixed().unwrap().file_version(), [1, 2, 3, 4]);
+    assert_eq!(info.string("CompanyName"), Some("Acme"));
+
+    Ok(())
+}
+
+#[test]
+fn it_computes_checksum() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    assert_eq!(
+        pe.compute_checksum(),
+        pe.nt_header().optional_header.check_sum
+    );
+
+    Ok(())
+}
+
+#[test]
+fn it_fixes_checksum() -> Result<(), Box<dyn Error>> {
+    let mut buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    let mut pe = PeViewMut::parse(&mut buf)?;
+
+    pe.fix_checksum();
+    let checksum = pe.view()?.nt_header().optional_header.check_sum;
+    assert_eq!(pe.view()?.compute_checksum(), checksum);
+
+    pe.write_at_rva(0x1000, &[0x90])?;
+    assert_ne!(pe.view()?.compute_checksum(), checksum);
+
+    pe.fix_checksum();
+    let view = pe.view()?;
+    assert_eq!(
+        view.compute_checksum(),
+        view.nt_header().optional_header.check_sum
+    );
+
+    // Move the NT header to an odd offset, so the field straddles two words
+    let e_lfanew = u32::from_le_bytes(buf[0x3C..0x40].try_into()?) as usize;
+    buf.insert(e_lfanew, 0);
+    buf[0x3C..0x40].copy_from_slice(&(e_lfanew as u32 + 1).to_le_bytes());
+    let field = e_lfanew + 1 + 24 + 64;
+
+    let mut zeroed = buf.clone();
+    zeroed[field..field + 4].fill(0);
+    let mut sum = zeroed
+        .chunks(2)
+        .map(|w| u16::from_le_bytes([w[0], w.get(1).copied().unwrap_or(0)]) as u32)
+        .sum::<u32>();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    let expected = sum + buf.len() as u32;
+
+    for value in [0u32, 0xFFFF_FFFF] {
+        buf[field..field + 4].copy_from_slice(&value.to_le_bytes());
+        assert_eq!(PeView::parse_unchecked(&buf)?.compute_checksum(), expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_directory_bytes() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    for typ in [
+        DataDirectoryType::ExportTable,
+        DataDirectoryType::RelocationTable,
+        DataDirectoryType::CertificateTable,
+    ] {
+        let dir = pe.directory(typ).unwrap();
+        assert_eq!(pe.directory_bytes(typ)?.len(), dir.size as usize);
+    }
+
+    assert!(matches!(
+        pe.directory_bytes(DataDirectoryType::Reserved),
+        Err(peview::error::Error::DataDirectoryEmpty)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn it_converts_addresses() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    let entry = PeAddr::Rva(0x1000).offset(4);
+    assert_eq!(entry.as_rva(), Some(0x1004));
+    assert_eq!(entry.as_file_ptr(), None);
+    assert_eq!(pe.to_file_ptr(entry), Some(0x204));
+    assert!(std::ptr::eq(pe.bytes(), buf.as_slice()));
+    assert_eq!(pe.bytes()[0x204], 0xC3);
+    assert_eq!(pe.to_rva(PeAddr::FilePtr(0x204)), Some(0x1004));
+
+    // Headers map to themselves, while padding and zero-filled data are not mapped
+    assert_eq!(pe.to_rva(PeAddr::FilePtr(0x40)), Some(0x40));
+    assert_eq!(pe.to_rva(PeAddr::FilePtr(0x210)), None);
+    assert_eq!(pe.to_file_ptr(PeAddr::Rva(0x2000)), None);
+
+    let data = pe.section_data_at(PeAddr::FilePtr(0x204))?;
+    assert_eq!(data.bytes_at(0x1004)?[0], 0xC3);
+    assert!(matches!(
+        pe.section_data_at(PeAddr::Rva(0x2000)),
+        Err(peview::error::Error::SectionEmpty)
+    ));
+
+    assert_eq!(PeAddr::FilePtr(4).offset(-8), PeAddr::FilePtr(0));
+    assert_eq!(PeAddr::Rva(u32::MAX).offset(1), PeAddr::Rva(u32::MAX));
+
+    let addrs = [
+        PeAddr::FilePtr(4),
+        PeAddr::Rva(8),
+        PeAddr::Rva(2),
+        PeAddr::Rva(8),
+    ]
+    .into_iter()
+    .collect::<BTreeSet<_>>();
+    assert!(addrs.into_iter().eq([
+        PeAddr::Rva(2),
+        PeAddr::Rva(8),
+        PeAddr::FilePtr(4)
+    ]));
+
+    Ok(())
+}
+
+#[test]
+fn it_validates_layout() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    PeView::parse(&buf)?.validate_layout()?;
+
+    // Point either the raw data or the virtual range of .data at the one of .text
+    let header = mem::size_of::<DosHeader>()
+        + mem::size_of::<NtHeader>()
+        + mem::size_of::<SectionHeader>();
+    for (field, value) in [
+        (mem::offset_of!(SectionHeader, raw_data_address), 0x200u32),
+        (mem::offset_of!(SectionHeader, virtual_address), 0x1000),
+    ] {
+        let mut buf = buf.clone();
+        buf[header + field..][..4].copy_from_slice(&value.to_le_bytes());
+
+        assert!(PeView::parse(&buf)?.validate_layout().is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_validates_entry_point() -> Result<(), Box<dyn Error>> {
+    let builder = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10]);
+
+    for (entry, valid) in
+        [(0x1004, true), (0x2000, false), (0x100, false), (0, false)]
+    {
+        let buf = builder
+            .clone()
+            .optional_header(|h| h.address_of_entry_point = entry)
+            .to_bytes();
+        let result = PeView::parse(&buf)?.validate_entry_point();
+        match valid {
+            true => result?,
+            false => assert!(matches!(
+                result,
+                Err(peview::error::Error::Validation {
+                    rule: ValidationRule::EntryPoint(rva),
+                    ..
+                }) if rva == entry
+            )),
+        }
+    }
+
+    // Libraries may have no entry point
+    let buf = builder
+        .file_header(|h| h.characteristics |= 0x2000)
+        .to_bytes();
+    PeView::parse(&buf)?.validate_entry_point()?;
+
+    Ok(())
+}
+
+#[test]
+fn it_classifies_images() -> Result<(), Box<dyn Error>> {
+    let builder = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10]);
+
+    let buf = builder.to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert_eq!(pe.bitness(), Some(Bitness::Bits64));
+    for (magic, bitness) in [(0x10B, Some(Bitness::Bits32)), (0x107, None)] {
+        let buf = builder
+            .clone()
+            .optional_header(|h| h.magic = magic)
+            .to_bytes();
+        assert!(PeView::parse(&buf).is_err());
+        assert_eq!(PeView::parse_unchecked(&buf)?.bitness(), bitness);
+    }
+    assert!(!pe.is_dll());
+    assert!(!pe.is_driver());
+    assert!(!pe.is_dotnet());
+    assert!(!pe.relocations_stripped());
+    assert!(!pe.can_relocate());
+    assert_eq!(
+        pe.file_characteristics().flags_string(),
+        "EXECUTABLE_IMAGE|LARGE_ADDRESS_AWARE"
+    );
+    assert_eq!(pe.dll_characteristics().flags_string(), "");
+    assert_eq!(pe.sections()[0].flags_string(), "CODE|EXECUTE|READ");
+    assert_eq!(
+        pe.sections()[1].flags_string(),
+        "INITIALIZED_DATA|READ|WRITE"
+    );
+
+    let dir = DataDirectory {
+        addr: 0x2000,
+        size: 0x48,
+    };
+    let relocatable = builder
+        .clone()
+        .data_directory(DataDirectoryType::RelocationTable, dir);
+    let buf = relocatable.clone().to_bytes();
+    assert!(PeView::parse(&buf)?.can_relocate());
+
+    let buf = relocatable
+        .file_header(|h| h.characteristics |= 0x1)
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert!(pe.relocations_stripped());
+    assert!(!pe.can_relocate());
+
+    let buf = builder
+        .clone()
+        .data_directory(DataDirectoryType::CLRRuntimeHeader, dir)
+        .to_bytes();
+    assert!(PeView::parse(&buf)?.is_dotnet());
+
+    let buf = builder
+        .clone()
+        .file_header(|h| h.characteristics |= 0x2000)
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert!(pe.is_dll());
+    assert!(!pe.is_driver());
+
+    let buf = builder.optional_header(|h| h.subsystem = 1).to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert!(!pe.is_dll());
+    assert!(pe.is_driver());
+
+    let characteristics = DllCharacteristics(0x8160);
+    assert!(characteristics.contains(DllFlags::NxCompat));
+    assert_eq!(
+        characteristics.flags_string(),
+        "HIGH_ENTROPY_VA|DYNAMIC_BASE|NX_COMPAT|TERMINAL_SERVER_AWARE"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn it_finds_directory_containing() -> Result<(), Box<dyn Error>> {
+    let dir = |addr, size| DataDirectory { addr, size };
+    let buf = NtHeaderBuilder::new()
+        .data_directory(DataDirectoryType::ExportTable, dir(0x1000, 8))
+        .data_directory(DataDirectoryType::ImportAddressTable, dir(0x1008, 8))
+        .data_directory(DataDirectoryType::CertificateTable, dir(0x1010, 8))
+        .section(".text", TEXT, 0, &[0xC3; 0x20])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    let typ = |rva| pe.directory_containing(rva).map(|(typ, _)| typ);
+    assert_eq!(typ(0x1004), Some(DataDirectoryType::ExportTable));
+    assert_eq!(typ(0x1008), Some(DataDirectoryType::ImportAddressTable));
+    assert_eq!(typ(0x1010), None);
+
+    let section = |typ| pe.section_for_directory(typ).map(|s| s.name());
+    assert_eq!(section(DataDirectoryType::ExportTable), Some(".text"));
+    assert_eq!(section(DataDirectoryType::CertificateTable), None);
+    assert_eq!(section(DataDirectoryType::ImportTable), None);
+
+    Ok(())
+}
+
+#[test]
+fn it_rejects_nt_header_past_eof() {
+    let mut buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+
+    let len = buf.len() as u32;
+    for e_lfanew in [len, len - 4, u32::MAX - 3] {
+        buf[0x3C..0x40].copy_from_slice(&e_lfanew.to_le_bytes());
+        assert!(matches!(
+            PeView::parse(&buf),
+            Err(peview::error::Error::Validation {
+                rule: ValidationRule::NewHeaderRva(v),
+                ..
+            }) if v == e_lfanew
+        ));
+    }
+
+    // Without an MZ signature the buffer is not a PE, wherever e_lfanew points
+    buf[..2].copy_from_slice(b"ZM");
+    for bytes in [&buf[..], &[0; 0x40]] {
+        assert!(matches!(
+            PeView::parse(bytes),
+            Err(peview::error::Error::Validation {
+                rule: ValidationRule::Signature(_),
+                ..
+            })
+        ));
+    }
+}
+
+#[test]
+fn it_reports_validation_rules() {
+    let builder = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10]);
+
+    let buf = builder
+        .clone()
+        .optional_header(|h| h.image_base = 0x1234)
+        .to_bytes();
+    let err = PeView::parse(&buf).err().unwrap();
+    assert!(matches!(
+        err,
+        peview::error::Error::Validation {
+            structure,
+            rule: ValidationRule::ImageBase(0x1234)
+        } if structure.ends_with("OptionalHeader")
+    ));
+    assert!(err
+        .to_string()
+        .ends_with("has invalid image base (0x00000000001234)"));
+
+    let buf = builder.optional_header(|h| h.loader_flags = 1).to_bytes();
+    assert!(matches!(
+        PeView::parse(&buf),
+        Err(peview::error::Error::Validation {
+            rule: ValidationRule::ReservedField("loader_flags"),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn it_reads_unparsed_directories() -> Result<(), Box<dyn Error>> {
+    let dir = |addr, size| DataDirectory { addr, size };
+    let buf = NtHeaderBuilder::new()
+        .data_directory(DataDirectoryType::Architecture, dir(0x1000, 8))
+        .data_directory(DataDirectoryType::GlobalPointer, dir(0x1008, 0))
+        .data_directory(DataDirectoryType::Reserved, dir(0x9000, 8))
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    assert_eq!(pe.global_pointer(), Some(0x1008));
+    assert_eq!(
+        pe.directory(DataDirectoryType::Architecture)
+            .map(|d| d.size),
+        Some(8)
+    );
+
+    // Reserved directories and the global pointer hold no data to be read
+    for typ in [
+        DataDirectoryType::Architecture,
+        DataDirectoryType::GlobalPointer,
+        DataDirectoryType::Reserved,
+    ] {
+        assert!(matches!(
+            pe.directory_bytes(typ),
+            Err(peview::error::Error::UnsupportedDirectory(t)) if t == typ
+        ));
+    }
+
+    // Absent ones are reported as empty, like every other directory
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .to_bytes();
+    let empty = PeView::parse(&buf)?;
+    assert!(matches!(
+        empty.directory_bytes(DataDirectoryType::GlobalPointer),
+        Err(peview::error::Error::DataDirectoryEmpty)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_dos_stub() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    let e_lfanew = pe.dos_header().e_lfanew as usize;
+    assert_eq!(pe.dos_stub(), &buf[mem::size_of::<DosHeader>()..e_lfanew]);
+
+    // The builder places the NT-header directly after the DOS-header
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    assert!(PeView::parse(&buf)?.dos_stub().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn it_locates_headers() -> Result<(), Box<dyn Error>> {
+    let mut buf = Vec::new();
+    File::open("etc/exe/ntoskrnl.exe")?.read_to_end(&mut buf)?;
+    let pe = PeView::parse(&buf)?;
+
+    // The headers are copied out of the buffer, so compare their contents
+    let offset = pe.optional_header_offset();
+    let magic = u16::from_le_bytes([buf[offset], buf[offset + 1]]);
+    assert_eq!(magic, pe.nt_header().optional_header.magic);
+
+    let offset = pe.section_headers_offset();
+    assert_eq!(pe.sections()[0].header().name, buf[offset..offset + 8]);
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_unaligned_buffers() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+
+    // Shift the image by one byte, so none of the headers are aligned anymore
+    let mut shifted = vec![0u8; buf.len() + 1];
+    shifted[1..].copy_from_slice(&buf);
+    let bytes = &shifted[1..];
+    assert!(!(bytes.as_ptr() as usize).is_multiple_of(8));
+
+    let pe = PeView::parse(bytes)?;
+    assert_eq!(pe.nt_header().file_header.num_of_sections, 2);
+    assert_eq!(pe.section_by_name(".data").map(|s| s.name()), Some(".data"));
+
+    assert!(matches!(
+        OptionalHeader::from_bytes(&bytes[pe.optional_header_offset()..]),
+        Err(peview::error::Error::Misaligned)
+    ));
+    let header =
+        OptionalHeader::from_bytes_copied(&bytes[pe.optional_header_offset()..])?;
+    assert_eq!(header.magic, pe.nt_header().optional_header.magic);
+
+    Ok(())
+}
+
+#[test]
+fn it_owns_its_buffer() -> Result<(), Box<dyn Error>> {
+    fn open(buf: &[u8]) -> peview::error::Result<OwnedPeView> {
+        OwnedPeView::parse(buf.to_vec())
+    }
+
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+
+    let pe = open(&buf)?;
+    drop(buf);
+    assert_eq!(pe.view().sections().len(), 2);
+    assert_eq!(
+        pe.view().section_by_name(".text").map(|s| s.name()),
+        Some(".text")
+    );
+
+    assert!(OwnedPeView::parse(vec![0; 0x10]).is_err());
+
+    let bytes = pe.into_bytes();
+    let pe = OwnedPeView::parse_with(bytes.clone(), &ParseOptions::lenient())?;
+    assert!(pe.view().skipped_checks().is_empty());
+    assert_eq!(pe.into_bytes(), bytes);
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn it_reads_from_readers() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+
+    let pe = OwnedPeView::from_reader(std::io::Cursor::new(&buf))?;
+    assert_eq!(pe.view().bytes(), buf.as_slice());
+
+    struct Failing;
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::UnexpectedEof.into())
+        }
+    }
+
+    assert!(matches!(
+        OwnedPeView::from_reader(Failing),
+        Err(peview::error::Error::Io(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn it_fingerprints_layouts() -> Result<(), Box<dyn Error>> {
+    let builder = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0x100, &[0; 0x10])
+        .data_directory(
+            DataDirectoryType::ImportTable,
+            DataDirectory {
+                addr: 0x2000,
+                size: 0x10,
+            },
+        );
+    let buf = builder.to_bytes();
+    let fingerprint = PeView::parse(&buf)?.layout_fingerprint();
+
+    assert_eq!(
+        fingerprint.sections(),
+        [(".text".into(), TEXT, 0x10), (".data".into(), DATA, 0x100)]
+    );
+    assert_eq!(fingerprint.directories(), [DataDirectoryType::ImportTable]);
+
+    // Rebuilds differ in their timestamp and checksum only
+    let rebuilt = builder
+        .clone()
+        .file_header(|h| h.time_date_stamp = 0x12345678)
+        .optional_header(|h| h.check_sum = 0x1234)
+        .to_bytes();
+    assert_eq!(PeView::parse(&rebuilt)?.layout_fingerprint(), fingerprint);
+
+    let changed = builder.section(".rdata", RDATA, 0, &[0; 0x10]).to_bytes();
+    assert_ne!(PeView::parse(&changed)?.layout_fingerprint(), fingerprint);
+
+    Ok(())
+}
+
+#[test]
+fn it_diffs_sections() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0x100, &[0; 0x10])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert!(pe.diff_sections(&pe).is_empty());
+
+    // Patch two separate ranges of .text, as done when applying relocations
+    let text = pe
+        .section_by_name(".text")
+        .unwrap()
+        .header()
+        .raw_data_address;
+    let mut patched = buf.clone();
+    patched[text as usize + 2..][..2].fill(0x90);
+    patched[text as usize + 8] = 0x90;
+
+    let diff = pe.diff_sections(&PeView::parse(&patched)?);
+    assert_eq!(
+        diff,
+        [SectionDiff {
+            index: 0,
+            ranges: vec![0x1002..0x1004, 0x1008..0x1009]
+        }]
+    );
+
+    // Sections without a counterpart differ entirely
+    let other = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .to_bytes();
+    let diff = pe.diff_sections(&PeView::parse(&other)?);
+    #[allow(clippy::single_range_in_vec_init)]
+    let expected = [0x2000..0x2100];
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].ranges, expected);
+
+    // Sections at the end of the address space are compared up to its last RVA
+    let offset = pe.section_headers_offset();
+    let mut header = buf[offset..offset + 40].to_vec();
+    header[12..16].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+    let top = Section::parse(&buf, &header)?;
+    let diff = top.diff(&Section::parse(&patched, &header)?);
+    assert_eq!(diff, [0xFFFF_FFF2..0xFFFF_FFF4, 0xFFFF_FFF8..0xFFFF_FFF9]);
+    let nulls = vec![0; buf.len()];
+    let nulls = Section::parse(&nulls, &header)?;
+    #[allow(clippy::single_range_in_vec_init)]
+    let expected = [0xFFFF_FFF0..0xFFFF_FFFF];
+    assert_eq!(top.diff(&nulls), expected);
+
+    Ok(())
+}
+
+#[test]
+fn it_skips_within_bounds() -> Result<(), Box<dyn Error>> {
+    let buf = [0u8; 8];
+
+    let mut data = ByteReader::new(&buf);
+    assert_eq!(data.try_skip_to(Pos::Abs(8))?.remaining_bytes().len(), 0);
+    assert!(matches!(
+        data.try_skip_to(Pos::Abs(2))?.try_skip_to(Pos::Rel(7)),
+        Err(peview::error::Error::InsufficientBufferAt(9))
+    ));
+    assert_eq!(data.remaining_bytes().len(), 6);
+
+    // Absolute positions are RVA's for relative readers
+    let mut data = ByteReader::new_with_rel(&buf, 0x1000);
+    assert!(data.try_skip_to(Pos::Abs(0x10)).is_err());
+    assert_eq!(data.try_skip_to(Pos::Abs(0x1004))?.read::<u32>()?, &0);
+    assert_eq!((data.position(), data.absolute_position()), (8, 0x1008));
+
+    Ok(())
+}
+
+#[test]
+fn it_computes_section_entropy() -> Result<(), Box<dyn Error>> {
+    let uniform = (0..=255).cycle().take(0x200).collect::<Vec<u8>>();
+    let mixed = [&[0u8; 0x20][..], &[1; 0x10], &[2; 0x8], &[3; 0x5]].concat();
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &uniform)
+        .section(".rdata", RDATA, 0, &mixed)
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    // The raw data is padded to the file alignment with zeros
+    let expected = |bytes: &[u8]| {
+        let mut counts = [0usize; 256];
+        for &b in bytes {
+            counts[b as usize] += 1;
+        }
+
+        counts
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| c as f64 / bytes.len() as f64)
+            .map(|p| -p * p.log2())
+            .sum::<f64>()
+    };
+
+    for section in pe.sections().iter().filter(|s| !s.empty()) {
+        let bytes = section.data().as_ref().unwrap().bytes();
+        let entropy = section.entropy().unwrap();
+        assert!((entropy - expected(bytes)).abs() < 1e-9);
+    }
+
+    let data = pe.section_by_name(".data").unwrap();
+    assert!((data.entropy().unwrap() - 8.0).abs() < 1e-9);
+    assert!(pe.section_by_name(".bss").unwrap().entropy().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_embedded_images() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+
+    // Embed the image in a container with a header of its own
+    let container = [&[0xFF; 0x40][..], &buf, &[0xFF; 0x10]].concat();
+    let pe = PeView::parse_at(&container, 0x40)?;
+    assert_eq!(pe.offset(), 0x40);
+    assert!(PeView::parse(&container).is_err());
+
+    let text = pe.section_by_name(".text").unwrap();
+    let raw = text.header().raw_data_address as usize;
+    assert_eq!(pe.bytes()[raw], 0xC3);
+    assert_eq!(container[pe.offset() + raw], 0xC3);
+    assert_eq!(pe.rva_to_offset(0x1000), Some(raw as u32));
+
+    assert_eq!(PeView::parse(&buf)?.offset(), 0);
+    assert!(PeView::parse_at(&container, container.len() + 1).is_err());
+
+    // The view covers the rest of the container, which the checksum is computed over
+    assert_eq!(pe.bytes().len(), buf.len() + 0x10);
+    let trimmed = PeView::parse_at(&container[..0x40 + buf.len()], 0x40)?;
+    assert_eq!(
+        trimmed.compute_checksum(),
+        PeView::parse(&buf)?.compute_checksum()
+    );
+    assert_ne!(pe.compute_checksum(), trimmed.compute_checksum());
+
+    // Embedded files can be parsed with relaxed options as well
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .optional_header(|h| h.loader_flags = 1)
+        .to_bytes();
+    let container = [&[0xFF; 0x40][..], &buf].concat();
+    assert!(PeView::parse_at(&container, 0x40).is_err());
+
+    let pe = PeView::parse_at_with(&container, 0x40, &ParseOptions::lenient())?;
+    assert_eq!(pe.offset(), 0x40);
+    assert!(pe.skipped_checks().contains(Check::ReservedField));
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_debug_entries() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x1000;
+    const RAW: u32 = 0x200;
+
+    // Three debug directory entries, followed by their data
+    let mut data = Vec::new();
+    for (typ, offset, size) in [(2u32, 0x60, 0x1e), (16, 0x80, 8), (13, 0x90, 0x14)]
+    {
+        for field in [0, 0, 0, typ, size, RVA + offset, RAW + offset] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+    }
+    data.resize(0x60, 0);
+    data.extend_from_slice(b"RSDS");
+    data.extend_from_slice(&[0xAB; 16]);
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(b"a.pdb\0");
+    data.resize(0x80, 0);
+    data.extend_from_slice(&4u32.to_le_bytes());
+    data.extend_from_slice(&[1, 2, 3, 4]);
+    data.resize(0x90, 0);
+    data.extend_from_slice(b"PGU\0");
+    data.extend_from_slice(&0x2000u32.to_le_bytes());
+    data.extend_from_slice(&0x10u32.to_le_bytes());
+    data.extend_from_slice(b".text\0\0\0");
+
+    let buf = NtHeaderBuilder::new()
+        .file_header(|h| h.time_date_stamp = 0x5F5E_1000)
+        .section(".rdata", RDATA, 0, &data)
+        .data_directory(
+            DataDirectoryType::Debug,
+            DataDirectory {
+                addr: RVA,
+                size: 3 * 28,
+            },
+        )
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert_eq!(pe.debug()?.count(), 3);
+    assert!(pe.is_reproducible_build());
+
+    let mut entries = pe.debug_entries()?;
+    let Some(DebugEntry::CodeView(cv)) = entries.next().transpose()? else {
+        panic!("expected a CodeView entry");
+    };
+    assert_eq!((cv.guid(), cv.age(), cv.path()), ([0xAB; 16], 3, "a.pdb"));
+
+    let Some(DebugEntry::Repro(hash)) = entries.next().transpose()? else {
+        panic!("expected a repro entry");
+    };
+    assert_eq!(hash, [1, 2, 3, 4]);
+
+    let Some(DebugEntry::Pogo(pogo)) = entries.next().transpose()? else {
+        panic!("expected a POGO entry");
+    };
+    assert_eq!(pogo.signature(), u32::from_le_bytes(*b"PGU\0"));
+    assert_eq!(
+        pogo.collect_all()?,
+        [PogoEntry {
+            rva: 0x2000,
+            size: 0x10,
+            name: ".text"
+        }]
+    );
+    assert!(entries.next().is_none());
+
+    let entry = DebugDirectoryEntry {
+        characteristics: 0,
+        time_date_stamp: 0,
+        major_version: 0,
+        minor_version: 0,
+        typ: DebugDirectoryEntry::TYPE_VC_FEATURE,
+        size_of_data: 20,
+        address_of_raw_data: 0,
+        pointer_to_raw_data: 0,
+    };
+    let data = [0, 12, 10, 8, 6].map(u32::to_le_bytes).concat();
+    let Ok(DebugEntry::VcFeature(features)) = DebugEntry::parse(&entry, &data)
+    else {
+        panic!("expected a VC feature entry");
+    };
+    assert_eq!(
+        (
+            features.c_cpp_count,
+            features.gs_count,
+            features.guard_count
+        ),
+        (12, 10, 6)
+    );
+    assert!(DebugEntry::parse(&entry, &data[..16]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn it_detects_reproducible_builds() -> Result<(), Box<dyn Error>> {
+    for (timestamp, reproducible) in [
+        (0, true),
+        (0x2000_0000, true),
+        (0x5F5E_1000, false),
+        (0x9A3F_22C1, true),
+        (u32::MAX, true),
+    ] {
+        let buf = NtHeaderBuilder::new()
+            .file_header(|h| h.time_date_stamp = timestamp)
+            .section(".text", TEXT, 0, &[0xC3])
+            .to_bytes();
+        assert_eq!(PeView::parse(&buf)?.is_reproducible_build(), reproducible);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn it_fuses_table_iterators() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x2000;
+    fn assert_fused<T>(mut iter: impl FusedIterator<Item = T>) {
+        for _ in 0..3 {
+            assert!(iter.next().is_none());
+        }
+    }
+
+    // Two IDT entries separated by the null terminator, each with a terminated ILT
+    let mut buf = Vec::new();
+    for field in [RVA + 0x3c, 0, 0, RVA + 0x50, RVA + 0x3c, 0, 0, 0, 0, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [RVA + 0x3c, 0, 0, RVA + 0x50, RVA + 0x3c] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [0x8000_0001u32, 0, 0x8000_0002, 0, 0] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    buf.extend_from_slice(b"A.dll\0");
+
+    let dir = DataDirectory {
+        addr: RVA,
+        size: buf.len() as u32,
+    };
+    let mut modules = ImportTable::new(&buf, &dir).with_bitness(Bitness::Bits32);
+    let mut module = modules.next().ok_or("no module")??;
+    assert!(matches!(module.next(), Some(Ok(Import::Ordinal(1)))));
+    assert_fused(module);
+    assert_fused(modules);
+
+    // A relocation block past the terminating empty block is never read
+    let mut buf = Vec::new();
+    for field in [0x1000u32, 12, 0xA008, 0, 0, 0x2000, 12, 0xA010] {
+        buf.extend_from_slice(&field.to_le_bytes());
+    }
+    let mut blocks = RelocationTable::new(&buf, &dir);
+    let mut block = blocks.next().ok_or("no block")??;
+    assert_eq!(block.next().transpose()?.map(|r| r.rva), Some(0x1008));
+    assert!(block.next().transpose()?.is_some());
+    assert_fused(block);
+    assert_fused(blocks);
+
+    // Iterating ends after the first error
+    let mut exports = ExportTable::new(&[0; 4], &dir);
+    assert!(exports.next().is_some_and(|e| e.is_err()));
+    assert_fused(exports);
+
+    Ok(())
+}
+
+#[test]
+fn it_locates_tls_callbacks() -> Result<(), Box<dyn Error>> {
+    const BASE: u64 = 0x140000000;
+
+    // TLS directory followed by the callback array
+    let mut data = Vec::new();
+    for field in [0, 0, BASE + 0x2030, BASE + 0x2028, 0] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    for field in [BASE + 0x1004, BASE + 0x1008, 0] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+
+    let build = |data: &[u8]| {
+        NtHeaderBuilder::new()
+            .section(".text", TEXT, 0, &[0xC3; 0x10])
+            .section(".rdata", RDATA, 0, data)
+            .data_directory(
+                DataDirectoryType::TLSTable,
+                DataDirectory {
+                    addr: 0x2000,
+                    size: 40,
+                },
+            )
+            .to_bytes()
+    };
+    let buf = build(&data);
+    let pe = PeView::parse(&buf)?;
+    let tls = pe.tls()?;
+    assert_eq!(tls.directory().address_of_index, BASE + 0x2030);
+    assert_eq!(
+        tls.callbacks(&pe).collect::<Result<Vec<_>, _>>()?,
+        [BASE + 0x1004, BASE + 0x1008]
+    );
+
+    let located = tls
+        .callbacks_located(&pe)
+        .map(|c| c.map(|(s, offset)| (s.name(), offset)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(located, [(".text", 4), (".text", 8)]);
+
+    // Callbacks outside of every section can not be located
+    data[40..48].copy_from_slice(&(BASE + 0x8000).to_le_bytes());
+    let buf = build(&data);
+    let pe = PeView::parse(&buf)?;
+    let mut located = pe.tls()?.callbacks_located(&pe).collect::<Vec<_>>();
+    assert!(located.remove(0).is_err());
+    assert!(located.remove(0).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn it_parses_dynamic_relocations() -> Result<(), Box<dyn Error>> {
+    // Load configuration, which is truncated after the reference to the table
+    let mut data = vec![0; 0xF0];
+    data[..4].copy_from_slice(&0xF0u32.to_le_bytes());
+    data[224..228].copy_from_slice(&0x100u32.to_le_bytes());
+    data[228..230].copy_from_slice(&1u16.to_le_bytes());
+
+    // Blocks of ARM64X fixups, the second padded and the last of an unknown type
+    let mut fixups = Vec::new();
+    for (page, records) in [
+        (
+            0x1000u32,
+            &[0x9010u16, 0xBEEF, 0xDEAD, 0xC020, 0xE030, 3][..],
+        ),
+        (0x2000, &[0x4008, 0]),
+        (0x3000, &[0x3000, 0]),
+    ] {
+        fixups.extend_from_slice(&page.to_le_bytes());
+        fixups.extend_from_slice(&(8 + records.len() as u32 * 2).to_le_bytes());
+        for record in records {
+            fixups.extend_from_slice(&record.to_le_bytes());
+        }
+    }
+
+    // Version 1 table, holding a single ARM64X relocation
+    data.resize(0x100, 0);
+    let size = fixups.len() as u32;
+    for field in [1u32, 12 + size, 6, 0, size] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(&fixups);
+
+    let buf = NtHeaderBuilder::new()
+        .section(".rdata", RDATA, 0, &data)
+        .data_directory(
+            DataDirectoryType::LoadConfigTable,
+            DataDirectory {
+                addr: 0x1000,
+                size: 0x40,
+            },
+        )
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    let config = pe.load_config()?;
+    assert_eq!(config.directory().size, 0xF0);
+    assert_eq!(config.directory().volatile_metadata_pointer, 0);
+    assert_eq!(config.dynamic_value_reloc_table(), Some((1, 0x100)));
+
+    let mut relocations = pe.dynamic_relocations()?.ok_or("no table")?;
+    let relocation = relocations.next().transpose()?.ok_or("no relocation")?;
+    assert_eq!(relocation.symbol, DynamicRelocation::ARM64X);
+    assert_eq!(relocation.data(), fixups);
+
+    let mut fixups = relocation.arm64x_fixups().ok_or("no fixups")?;
+    let value = 0xDEAD_BEEFu32.to_le_bytes();
+    for (rva, kind) in [
+        (0x1010, Arm64XFixupKind::Value(&value)),
+        (0x1020, Arm64XFixupKind::ZeroFill(8)),
+        (0x1030, Arm64XFixupKind::Delta(-24)),
+        (0x2008, Arm64XFixupKind::ZeroFill(2)),
+    ] {
+        assert_eq!(fixups.next().transpose()?, Some(Arm64XFixup { rva, kind }));
+    }
+    assert!(fixups.next().is_some_and(|f| f.is_err()));
+    assert!(fixups.next().is_none());
+    assert!(relocations.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn it_reads_unaligned_dynamic_relocations() -> Result<(), Box<dyn Error>> {
+    // The first entry is 20 bytes long, so the second one is only 4 byte aligned
+    let mut table = Vec::new();
+    for field in [1u32, 32, 6, 0, 8, 0, 0, 7, 0, 0] {
+        table.extend_from_slice(&field.to_le_bytes());
+    }
+
+    let relocations = DynamicRelocationTable::parse(&table)?
+        .map(|r| r.map(|r| (r.symbol, r.data().len())))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(relocations, [(DynamicRelocation::ARM64X, 8), (7, 0)]);
+
+    Ok(())
+}
+
+#[test]
+fn it_maps_images() -> Result<(), Box<dyn Error>> {
+    let mut buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .section(".bss", BSS, 0x100, &[])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    let headers = pe.size_of_headers() as usize;
+
+    let mut image = vec![0xFF; pe.size_of_image() as usize + 0x10];
+    pe.map_image(&mut image)?;
+    assert_eq!(image[..headers], buf[..headers]);
+    assert_eq!(image[0x1000..0x1010], [0xC3; 0x10]);
+    assert!(image[0x1010..0x3000].iter().all(|&b| b == 0));
+    assert_eq!(image[0x3000..], [0xFF; 0x10]);
+
+    let mapped = PeView::parse_mapped(&image[..0x3000])?;
+    assert_eq!(mapped.rva_to_offset(0x1004), Some(0x1004));
+
+    let mut small = vec![0; pe.size_of_image() as usize - 1];
+    assert!(pe.map_image(&mut small).is_err());
+
+    // Shrink the image, so that the .bss section exceeds it
+    let offset =
+        pe.optional_header_offset() + mem::offset_of!(OptionalHeader, size_of_image);
+    buf[offset..offset + 4].copy_from_slice(&0x2000u32.to_le_bytes());
+    let pe = PeView::parse_unchecked(&buf)?;
+    assert!(pe.map_image(&mut image).is_err());
+    assert!(image[0x1000..0x1010].iter().all(|&b| b == 0xC3));
+
+    Ok(())
+}
+
+#[test]
+fn it_counts_symbols() -> Result<(), Box<dyn Error>> {
+    let builder = NtHeaderBuilder::new().section(".text", TEXT, 0, &[0xC3; 0x10]);
+    let buf = builder.to_bytes();
+    let pe = PeView::parse(&buf)?;
+    assert!(!pe.has_symbols());
+    assert_eq!(pe.symbol_count(), 0);
+
+    // A single symbol followed by an empty string table
+    let mut symbols = b"main\0\0\0\0".to_vec();
+    symbols.extend_from_slice(&0x10u32.to_le_bytes());
+    symbols.extend_from_slice(&1i16.to_le_bytes());
+    symbols.extend_from_slice(&[0x20, 0, 2, 0]);
+    symbols.extend_from_slice(&4u32.to_le_bytes());
+
+    let offset = buf.len() as u32;
+    let mut buf = builder
+        .file_header(|h| {
+            h.ptr_to_symbol_table = offset;
+            h.num_of_symbols = 1;
+        })
+        .to_bytes();
+    buf.extend_from_slice(&symbols);
+
+    let pe = PeView::parse(&buf)?;
+    assert!(pe.has_symbols());
+    assert_eq!(pe.symbol_count(), 1);
+    assert_eq!(
+        pe.symbols()?
+            .next()
+            .transpose()?
+            .ok_or("no symbol")?
+            .name()?,
+        "main"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn it_resolves_long_section_names() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section("/4", RELOC, 0, &[0; 0x10])
+        .section("/16", RELOC, 0, &[0; 0x10])
+        .section("/x", RELOC, 0, &[0; 0x10])
+        .section(".text", TEXT, 0, &[0xC3; 0x10])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+
+    // The last string runs up to the declared end of the table without a terminator
+    let mut bytes = 19u32.to_le_bytes().to_vec();
+    bytes.extend_from_slice(b".debug_info\0abc");
+    bytes.extend_from_slice(b"def\0");
+    let strings = StringTable::parse(&bytes)?;
+
+    assert_eq!(strings.size(), 19);
+    assert_eq!(strings.get(4)?, ".debug_info");
+    assert_eq!(strings.get(9)?, "g_info");
+    assert!(strings.get(0).is_err());
+    assert!(strings.get(3).is_err());
+    assert!(strings.get(16).is_err());
+    assert!(strings.get(19).is_err());
+    assert!(strings.get(u32::MAX).is_err());
+    assert!(StringTable::parse(&bytes[..18]).is_err());
+
+    let sections = pe.sections();
+    assert_eq!(sections[0].long_name(&strings)?, ".debug_info");
+    assert!(sections[1].long_name(&strings).is_err());
+    assert_eq!(sections[2].long_name(&strings)?, "/x");
+    assert_eq!(sections[3].long_name(&strings)?, ".text");
+
+    Ok(())
+}
+
+#[test]
+fn it_restricts_machines() -> Result<(), Box<dyn Error>> {
+    let builder = NtHeaderBuilder::new().section(".text", TEXT, 0, &[0xC3; 0x10]);
+    let buf = builder.to_bytes();
+    let amd64 = ParseOptions {
+        allowed_machines: &[FileMachine::AMD64],
+        ..ParseOptions::strict()
+    };
+    assert!(PeView::parse_with(&buf, &amd64).is_ok());
+
+    let buf = builder
+        .clone()
+        .file_header(|h| h.machine = FileMachine::I386 as u16)
+        .to_bytes();
+    assert!(PeView::parse(&buf).is_ok());
+    assert!(matches!(
+        PeView::parse_with(&buf, &amd64),
+        Err(peview::error::Error::Validation {
+            rule: ValidationRule::Machine(0x014c),
+            ..
+        })
+    ));
+
+    // ARM64 images, including ARM64X ones, are accepted by default
+    let buf = builder.file_header(|h| h.machine = 0xAA64).to_bytes();
+    assert!(PeView::parse(&buf).is_ok());
+    assert!(PeView::parse_with(&buf, &amd64).is_err());
+
+    // The allowlist is a policy, which is not enforced without validation
+    let none = ParseOptions {
+        allowed_machines: &[],
+        ..ParseOptions::unchecked()
+    };
+    assert!(PeView::parse_with(&buf, &none).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn it_patches_mapped_images() -> Result<(), Box<dyn Error>> {
+    let buf = NtHeaderBuilder::new()
+        .section(".text", TEXT, 0x20, &[0xC3; 0x10])
+        .section(".data", DATA, 0, &[0; 0x10])
+        .to_bytes();
+    let pe = PeView::parse(&buf)?;
+    let mut image = vec![0; pe.size_of_image() as usize];
+    pe.map_image(&mut image)?;
+
+    let opts = ParseOptions {
+        mapped: true,
+        ..ParseOptions::strict()
+    };
+    let mut pe = PeViewMut::parse_with(&mut image, &opts)?;
+    let mut text = pe.section_by_name_mut(".text").ok_or("no section")?;
+    assert_eq!(text.data().len(), 0x20);
+    text.data_mut()[0] = 0xCC;
+
+    // The zero-filled part of a section is backed by the mapped image
+    pe.write_at_rva(0x1018, &[0x90; 4])?;
+    assert!(pe.write_at_rva(0x101E, &[0x90; 4]).is_err());
+
+    let view = pe.view()?;
+    let text = view.section_by_name(".text").unwrap();
+    assert_eq!(text.data().as_ref().unwrap().bytes()[..2], [0xCC, 0xC3]);
+    assert_eq!(image[0x1000], 0xCC);
+    assert_eq!(image[0x1018..0x101C], [0x90; 4]);
+    assert_eq!(image[0x200], 0);
+
     Ok(())
 }
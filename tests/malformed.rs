@@ -0,0 +1,125 @@
+use peview::{
+    dir::{DataDirectory, DataDirectoryTable, DataDirectoryType, ImportTable},
+    file::PeView,
+    header::{NtHeaderBuilder, SectionFlags},
+};
+use std::error::Error;
+
+// Characteristics of a read-only data section
+const RDATA: u32 = SectionFlags::CntInitData as u32 | SectionFlags::Read as u32;
+
+#[test]
+fn it_never_panics_on_malformed_input() -> Result<(), Box<dyn Error>> {
+    const RVA: u32 = 0x1000;
+
+    // Import, export, relocation and debug directories within a single section
+    let mut data = Vec::new();
+    for field in [RVA + 0x40, 0, 0, RVA + 0x60, RVA + 0x40] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.resize(0x40, 0);
+    for field in [0x8000_0000_0000_0007, RVA as u64 + 0x68] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.resize(0x60, 0);
+    data.extend_from_slice(b"A.dll\0\0\0\x02\0Foo\0");
+    data.resize(0x80, 0);
+    let edt = [
+        0,
+        0,
+        0,
+        RVA + 0xC0,
+        1,
+        1,
+        1,
+        RVA + 0xA8,
+        RVA + 0xAC,
+        RVA + 0xB0,
+    ];
+    for field in edt.into_iter().chain([RVA + 0x10, RVA + 0xB4, 0]) {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(b"Foo\0\0\0\0\0\0\0\0\0m.dll\0");
+    data.resize(0xD0, 0);
+    for field in [0x1000u32, 12, 0xA008] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.resize(0xE0, 0);
+    for field in [0, 0, 0, 16, 8, RVA + 0x100, 0x300, 4, 0x0403_0201] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+
+    let dir = |addr, size| DataDirectory { addr, size };
+    let mut image = NtHeaderBuilder::new()
+        .section(".rdata", RDATA, 0, &data)
+        .data_directory(DataDirectoryType::ImportTable, dir(RVA, 40))
+        .data_directory(DataDirectoryType::ExportTable, dir(RVA + 0x80, 0x48))
+        .data_directory(DataDirectoryType::RelocationTable, dir(RVA + 0xD0, 12))
+        .data_directory(DataDirectoryType::Debug, dir(RVA + 0xE0, 28))
+        .data_directory(DataDirectoryType::CertificateTable, dir(0x400, 16))
+        .to_bytes();
+    image.resize(0x400, 0);
+    for field in [16u32, 0x0002_0200, 0xAAAA_AAAA, 0xAAAA_AAAA] {
+        image.extend_from_slice(&field.to_le_bytes());
+    }
+
+    fn exercise(bytes: &[u8]) {
+        for pe in [PeView::parse(bytes), PeView::parse_unchecked(bytes)] {
+            let Ok(pe) = pe else { continue };
+
+            pe.exports().into_iter().flatten().for_each(drop);
+            for module in pe.imports().into_iter().flatten().flatten() {
+                let _ = module.name();
+                module.entries().for_each(drop);
+            }
+            for block in pe.relocations().into_iter().flatten().flatten() {
+                block.for_each(drop);
+            }
+            pe.certificates().into_iter().flatten().for_each(drop);
+            pe.debug_entries().into_iter().flatten().for_each(drop);
+            let _ = pe.version_info();
+            let _ = pe.load_config();
+            let _ = pe.validate_layout();
+            for section in pe.sections() {
+                let _ = section.entropy();
+            }
+        }
+    }
+
+    let pe = PeView::parse(&image)?;
+    assert_eq!(pe.exports()?.count(), 1);
+    assert_eq!(pe.imports()?.next().ok_or("no module")??.count(), 2);
+    assert_eq!(pe.relocation_count()?, 2);
+    assert_eq!(pe.certificate_count()?, 1);
+    assert!(pe.is_reproducible_build());
+
+    // Every truncation of the image
+    for len in 0..image.len() {
+        exercise(&image[..len]);
+    }
+
+    // Random corruptions of a few bytes, seeded for reproducibility
+    let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    for _ in 0..5000 {
+        let mut bytes = image.clone();
+        for _ in 0..1 + next() % 4 {
+            let pos = next() as usize % bytes.len();
+            bytes[pos] = next() as u8;
+        }
+        exercise(&bytes);
+    }
+
+    // Misaligned tables fail once instead of repeating the same error forever
+    let dir = dir(RVA, 40);
+    let mut modules = ImportTable::new(&image[0x201..], &dir);
+    assert!(modules.next().is_some_and(|m| m.is_err()));
+    assert!(modules.next().is_none());
+
+    Ok(())
+}